@@ -12,10 +12,10 @@
 //! let filepath = Path::new("./data/config_sample.toml");
 //! let conf = Config::from_file(&filepath).unwrap();
 //!
-//! # assert_eq!(conf.extract_data().keys().len(), 23);
+//! # assert_eq!(conf.extract_data().keys().len(), 21);
 //! # #[cfg(feature = "rhai")]
 //! # assert_eq!(conf.extract_translators().unwrap().keys().len(), 2);
-//! # assert_eq!(conf.extract_translation().keys().len(), 4);
+//! # assert_eq!(conf.extract_translation().keys().len(), 5);
 //! ```
 //!
 //! In case that you want control the filesystem (reading of file), you can use the
@@ -57,10 +57,9 @@
 //! let config = Config::from_filesystem(&Path::new("."), &config_file).unwrap();
 //!
 //! assert_eq!(config.core.clone().unwrap().auto_commit, Some(false));
-//! // Note that the auto_capitalize is enabled by default.
 //! assert_eq!(
 //!     Vec::from_iter(config.extract_data().into_iter()),
-//!     vec![("N*".to_owned(), "Ŋ".to_owned()), ("n*".to_owned(), "ŋ".to_owned())]
+//!     vec![("n*".to_owned(), "ŋ".to_owned())]
 //! );
 //! ```
 
@@ -69,7 +68,10 @@ use indexmap::IndexMap;
 #[cfg(feature = "rhai")]
 use rhai::{Engine, AST};
 use serde::Deserialize;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use toml::{self};
 
 /// Trait to customize the filesystem.
@@ -127,10 +129,13 @@ impl FileSystem for StdFileSystem {
 /// hey = "hi"
 /// hi = { value = "hello", alias = ["hey"] }
 /// hola = { values = ["hello"], alias = [] }
-/// dictionary = { path = "./dictionary.toml" }
+/// dictionary = { path = "./dictionary.toml", priority = 1 }
 ///
 /// [translators]
 /// date = "./scripts/datetime/date.rhai"
+///
+/// [profiles]
+/// ewondo = "./ewondo/ewondo.toml"
 /// # "#.to_owned());
 ///
 /// # // Loads the config file.
@@ -144,8 +149,29 @@ pub struct Config {
     #[cfg(feature = "rhai")]
     translators: Option<IndexMap<String, Data>>,
     translation: Option<IndexMap<String, Data>>,
+    #[serde(skip)]
+    dictionaries: IndexMap<String, Dictionary>,
+    profiles: Option<IndexMap<String, String>>,
+    #[serde(skip)]
+    directory: PathBuf,
+}
+
+/// The translations of a named source.
+///
+/// Each file included in the translation table of the root configuration file is a dictionary
+/// named after its key. The translations written in the root configuration file are in the
+/// [`MAIN_DICTIONARY`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dictionary {
+    /// The priority of the dictionary, the higher the better.
+    pub priority: i32,
+    /// The translations of the dictionary.
+    pub translation: IndexMap<String, Vec<String>>,
 }
 
+/// The name of the dictionary of the translations written in the root configuration file.
+pub const MAIN_DICTIONARY: &str = "main";
+
 /// Core information about a configuration.
 ///
 /// # Example
@@ -176,23 +202,94 @@ pub struct Config {
 /// [core]
 /// buffer_size = 32
 /// auto_capitalize = false
+/// case_mapping = { "ɛ" = "Ɛ", "i" = "İ" }
 /// page_size = 10
 /// auto_commit = true
+/// escape_key = "\\"
+/// revert_key = "F8"
+/// profile_key = "F9"
+/// sequence_timeout = 800
+/// separators = [" ", ".", ",", "?", "!"]
+/// commit_on_separator = true
+/// history_size = 10
+/// physical_keys = false
+/// alternates = true
+/// modifier_keys = ["1", "2", "3"]
+/// ranking_file = "ranking.json"
+/// user_dictionary_file = "user_dictionary.json"
+/// max_distance = 2
+/// similarity_threshold = 0.7
+/// script_max_operations = 100000
+/// script_timeout = 100
+/// script_max_string_size = 4096
+/// script_max_array_size = 1024
 /// # "#.to_owned());
 /// #
 /// # // Loads the config file.
-/// # Config::from_filesystem(&Path::new("."), &config_file).unwrap();
+/// # let config = Config::from_filesystem(&Path::new("./config.toml"), &config_file).unwrap();
+/// # let core = config.core.unwrap();
+/// # assert_eq!(core.ranking_file, Some("./ranking.json".to_owned()));
+/// # assert_eq!(core.user_dictionary_file, Some("./user_dictionary.json".to_owned()));
 /// ```
 #[derive(Deserialize, Debug, Clone)]
 pub struct CoreConfig {
     /// The size of the memory (history).
     /// The number of elements that should be tracked.
     pub buffer_size: Option<usize>,
-    auto_capitalize: Option<bool>,
+    /// Whether the sequences should be capitalized at runtime.
+    pub auto_capitalize: Option<bool>,
+    /// The language-specific uppercase of some characters.
+    pub case_mapping: Option<IndexMap<char, String>>,
     /// The max numbers of predicates to display.
     pub page_size: Option<usize>,
     /// Whether the predicate should be automatically committed.
+    ///
+    /// The corrections and the completions of the input are never committed automatically.
     pub auto_commit: Option<bool>,
+    /// The key used to send the next character through untransformed.
+    ///
+    /// Pressed twice, it sends the next characters through untransformed until the sequence ends.
+    pub escape_key: Option<char>,
+    /// The key which, pressed with Ctrl, restores the raw keys of the last conversion.
+    ///
+    /// The keys are named after their position on a US keyboard (e.g. "F8", "KeyR").
+    pub revert_key: Option<String>,
+    /// The key which, pressed with Ctrl, switches to the next profile.
+    pub profile_key: Option<String>,
+    /// The delay (in milliseconds) after which the current sequence is ended.
+    pub sequence_timeout: Option<u64>,
+    /// The characters that end the current word.
+    pub separators: Option<Vec<char>>,
+    /// Whether the top predicate should be committed at the end of a word.
+    ///
+    /// Like with `auto_commit`, the corrections and the completions of the input are skipped.
+    pub commit_on_separator: Option<bool>,
+    /// The max number of committed words to remember.
+    pub history_size: Option<usize>,
+    /// Whether the sequences are typed according to the physical keys of a US QWERTY keyboard.
+    pub physical_keys: Option<bool>,
+    /// Whether holding a key offers the alternates reachable from the current sequence.
+    pub alternates: Option<bool>,
+    /// The keys that can be typed before or after the rest of a sequence (e.g. the tones).
+    pub modifier_keys: Option<Vec<char>>,
+    /// The file where the selections of the user are saved, relative to the config file.
+    pub ranking_file: Option<String>,
+    /// The file where the words of the user are saved, relative to the config file.
+    ///
+    /// They are layered over the translation table.
+    pub user_dictionary_file: Option<String>,
+    /// The max number of typos corrected in a code.
+    pub max_distance: Option<usize>,
+    /// The similarity (between 0 and 1) above which a code with typos is corrected.
+    pub similarity_threshold: Option<f64>,
+    /// The max number of operations of a translator on each call.
+    pub script_max_operations: Option<u64>,
+    /// The delay (in milliseconds) after which a translator is aborted.
+    pub script_timeout: Option<u64>,
+    /// The max length of the strings of a translator.
+    pub script_max_string_size: Option<usize>,
+    /// The max length of the arrays of a translator.
+    pub script_max_array_size: Option<usize>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -208,6 +305,8 @@ enum Data {
 #[derive(Deserialize, Debug, Clone)]
 struct DataFile {
     path: String,
+    // The priority of a dictionary.
+    priority: Option<i32>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -230,9 +329,7 @@ fn capitalize(value: &str) -> Option<String> {
         return None;
     }
     let mut cap_key = String::with_capacity(value.len());
-    for c in first_char.to_uppercase() {
-        cap_key.push(c);
-    }
+    cap_key.extend(first_char.to_uppercase());
     cap_key.push_str(chars.as_str());
     Some(cap_key)
 }
@@ -248,7 +345,7 @@ impl Config {
         let mut data = IndexMap::new();
         #[cfg(feature = "rhai")]
         let mut translators = IndexMap::new();
-        let mut translation = IndexMap::new();
+        let mut dictionaries = IndexMap::new();
 
         let content = fs
             .read_to_string(filepath)
@@ -257,7 +354,34 @@ impl Config {
             format!("Failed to parse the root configuration file {filepath:?}.")
         })?;
 
-        let root_core = root_config.core.clone();
+        let config_path = filepath.parent().unwrap();
+        let abs_path = |path: String| {
+            config_path
+                .join(path)
+                .into_os_string()
+                .into_string()
+                .unwrap()
+        };
+        let root_core = root_config.core.clone().map(|mut core| {
+            core.ranking_file = core.ranking_file.map(abs_path);
+            core.user_dictionary_file = core.user_dictionary_file.map(abs_path);
+
+            core
+        });
+        let profiles = root_config
+            .profiles
+            .iter()
+            .flatten()
+            .map(|(name, path)| {
+                let abs_path = config_path
+                    .join(path)
+                    .into_os_string()
+                    .into_string()
+                    .unwrap();
+
+                (name.to_owned(), abs_path)
+            })
+            .collect();
 
         // Pass the already-parsed config directly instead of calling
         // read_config, which would re-read and re-parse the root same file.
@@ -268,8 +392,14 @@ impl Config {
             &mut data,
             #[cfg(feature = "rhai")]
             &mut translators,
-            &mut translation,
+            &mut dictionaries,
+            None,
         )?;
+        let translation = dictionaries
+            .values()
+            .flat_map(|dictionary: &Dictionary| dictionary.translation.iter())
+            .map(|(key, values)| (key.to_owned(), Data::Multi(values.to_owned())))
+            .collect();
 
         Ok(Config {
             core: root_core,
@@ -277,6 +407,9 @@ impl Config {
             #[cfg(feature = "rhai")]
             translators: Some(translators),
             translation: Some(translation),
+            dictionaries,
+            profiles: Some(profiles),
+            directory: config_path.to_path_buf(),
         })
     }
 
@@ -288,7 +421,8 @@ impl Config {
         fs: &impl FileSystem,
         data: &mut IndexMap<String, Data>,
         #[cfg(feature = "rhai")] translators: &mut IndexMap<String, Data>,
-        translation: &mut IndexMap<String, Data>,
+        dictionaries: &mut IndexMap<String, Dictionary>,
+        source: Option<&str>,
     ) -> Result<()> {
         let content = fs
             .read_to_string(filepath)
@@ -303,31 +437,30 @@ impl Config {
             data,
             #[cfg(feature = "rhai")]
             translators,
-            translation,
+            dictionaries,
+            source,
         )
     }
 
-    /// Populates `data`, `translators`, and `translation` from an
+    /// Populates `data`, `translators` and `dictionaries` from an
     /// already-parsed [`Config`], recursively following path-valued entries
     /// via [`Self::read_config`].
+    ///
+    /// The `source` is the dictionary of the translations, `None` for the root file.
     fn process_config(
         config: Self,
         filepath: &Path,
         fs: &impl FileSystem,
         data: &mut IndexMap<String, Data>,
         #[cfg(feature = "rhai")] translators: &mut IndexMap<String, Data>,
-        translation: &mut IndexMap<String, Data>,
+        dictionaries: &mut IndexMap<String, Dictionary>,
+        source: Option<&str>,
     ) -> Result<()> {
         let config_path = filepath.parent().unwrap();
-        let auto_capitalize = config
-            .core
-            .as_ref()
-            .and_then(|c| c.auto_capitalize)
-            .unwrap_or(true);
 
         for (key, value) in config.data.unwrap_or_default() {
             match value {
-                Data::File(DataFile { path }) => {
+                Data::File(DataFile { path, .. }) => {
                     let nested = config_path.join(&path);
                     Self::read_config(
                         &nested,
@@ -335,29 +468,16 @@ impl Config {
                         data,
                         #[cfg(feature = "rhai")]
                         translators,
-                        translation,
+                        dictionaries,
+                        source,
                     )?;
                 }
-                Data::Simple(value) => {
-                    // Borrow key/value for the capitalized entry before moving
-                    // them into the main insert below.
-                    if auto_capitalize && !key.is_empty() {
-                        if let Some(cap_key) = capitalize(&key) {
-                            data.entry(cap_key)
-                                .or_insert_with(|| Data::Simple(value.to_uppercase()));
-                        }
-                    }
-                    data.insert(key, Data::Simple(value));
+                Data::Simple(_) => {
+                    data.insert(key, value);
                 }
                 Data::Detailed(DetailedData { value, alias }) => {
                     for k in alias.iter().chain(std::iter::once(&key)) {
                         data.insert(k.clone(), Data::Simple(value.clone()));
-                        if auto_capitalize {
-                            if let Some(cap_key) = capitalize(k) {
-                                data.entry(cap_key)
-                                    .or_insert_with(|| Data::Simple(value.to_uppercase()));
-                            }
-                        }
                     }
                 }
                 _ => Err(anyhow!("{value:?} not allowed in the data table."))
@@ -369,9 +489,9 @@ impl Config {
         #[cfg(feature = "rhai")]
         for (key, value) in config.translators.unwrap_or_default() {
             match value {
-                Data::File(DataFile { path }) => {
+                Data::File(DataFile { path, .. }) => {
                     let nested = config_path.join(&path);
-                    Self::read_config(&nested, fs, data, translators, translation)?;
+                    Self::read_config(&nested, fs, data, translators, dictionaries, source)?;
                 }
                 Data::Simple(path) => {
                     let abs_path = config_path
@@ -387,8 +507,15 @@ impl Config {
         }
 
         for (key, value) in config.translation.unwrap_or_default() {
+            let dictionary = source.unwrap_or(MAIN_DICTIONARY);
             match value {
-                Data::File(DataFile { path }) => {
+                Data::File(DataFile { path, priority }) => {
+                    // The files included in the root file are dictionaries.
+                    let dictionary = source.unwrap_or(&key);
+                    if source.is_none() {
+                        dictionaries.entry(key.clone()).or_default().priority =
+                            priority.unwrap_or_default();
+                    }
                     let nested = config_path.join(&path);
                     Self::read_config(
                         &nested,
@@ -396,20 +523,24 @@ impl Config {
                         data,
                         #[cfg(feature = "rhai")]
                         translators,
-                        translation,
+                        dictionaries,
+                        Some(dictionary),
                     )?;
                 }
-                Data::Simple(_) | Data::Multi(_) => {
-                    translation.insert(key, value);
+                Data::Simple(value) => {
+                    Self::insert_translation(dictionaries, dictionary, key, vec![value]);
+                }
+                Data::Multi(values) => {
+                    Self::insert_translation(dictionaries, dictionary, key, values);
                 }
                 Data::Detailed(DetailedData { value, alias }) => {
-                    for e in alias.iter().chain(std::iter::once(&key)) {
-                        translation.insert(e.clone(), Data::Simple(value.clone()));
+                    for e in alias.into_iter().chain(std::iter::once(key)) {
+                        Self::insert_translation(dictionaries, dictionary, e, vec![value.clone()]);
                     }
                 }
                 Data::MoreDetailed(MoreDetailedData { values, alias }) => {
-                    for k in alias.iter().chain(std::iter::once(&key)) {
-                        translation.insert(k.clone(), Data::Multi(values.clone()));
+                    for k in alias.into_iter().chain(std::iter::once(key)) {
+                        Self::insert_translation(dictionaries, dictionary, k, values.clone());
                     }
                 }
             }
@@ -418,6 +549,20 @@ impl Config {
         Ok(())
     }
 
+    // Inserts a translation in its dictionary.
+    fn insert_translation(
+        dictionaries: &mut IndexMap<String, Dictionary>,
+        dictionary: &str,
+        key: String,
+        values: Vec<String>,
+    ) {
+        dictionaries
+            .entry(dictionary.to_owned())
+            .or_default()
+            .translation
+            .insert(key, values);
+    }
+
     /// Extracts the data from the configuration.
     pub fn extract_data(&self) -> IndexMap<String, String> {
         // with_capacity avoids incremental reallocations during iteration.
@@ -433,6 +578,39 @@ impl Config {
         result
     }
 
+    /// Extracts the data from the configuration, along with their capitalized entries.
+    ///
+    /// The capitalization is now applied at runtime by the preprocessor (see
+    /// [`CoreConfig::auto_capitalize`]). This helper generates the uppercase entries as the
+    /// previous versions did, for the frontends which don't rely on the runtime capitalization.
+    /// The entries defined in the configuration take precedence over the generated ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_config::Config;
+    /// use std::path::Path;
+    ///
+    /// let config = Config::from_file(Path::new("./data/data_sample.toml")).unwrap();
+    /// let data = config.extract_data_with_capitalization();
+    ///
+    /// assert_eq!(data.get("U2"), Some(&"Ú".to_owned()));
+    /// assert_eq!(data.get("u2"), Some(&"ú".to_owned()));
+    /// ```
+    pub fn extract_data_with_capitalization(&self) -> IndexMap<String, String> {
+        let data = self.extract_data();
+        let mut result = IndexMap::with_capacity(data.len() * 2);
+        for (key, value) in &data {
+            if let Some(cap_key) = capitalize(key).filter(|k| !data.contains_key(k)) {
+                result
+                    .entry(cap_key)
+                    .or_insert_with(|| value.to_uppercase());
+            }
+            result.insert(key.clone(), value.clone());
+        }
+        result
+    }
+
     /// Extracts the translators from the configuration.
     #[cfg(feature = "rhai")]
     pub fn extract_translators(&self) -> Result<IndexMap<String, AST>> {
@@ -450,7 +628,9 @@ impl Config {
         let Some(translators) = &self.translators else {
             return Ok(IndexMap::new());
         };
-        let engine = Engine::new();
+        let mut engine = Engine::new();
+        // The debug builds of rhai have lower depths.
+        engine.set_max_expr_depths(64, 32);
         let mut result = IndexMap::with_capacity(translators.len());
 
         for (name, file_path) in translators {
@@ -473,6 +653,53 @@ impl Config {
         Ok(result)
     }
 
+    /// Returns the directory of the configuration file.
+    ///
+    /// The translators can only import the modules inside it.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Extracts the profiles from the configuration.
+    ///
+    /// A profile is an alternative configuration, loaded from its own file.
+    ///
+    /// The settings of the preprocessor (e.g. `separators` or `auto_capitalize`) missing from a
+    /// profile are taken from the main configuration. The `buffer_size`, `page_size`,
+    /// `auto_commit`, `commit_on_separator` and the keys of the special functions are always
+    /// taken from the main configuration.
+    pub fn extract_profiles(&self) -> Result<IndexMap<String, Config>> {
+        self.extract_profiles_using_filesystem(&StdFileSystem {})
+    }
+
+    /// Extracts the profiles from the configuration using the specified
+    /// filesystem.
+    pub fn extract_profiles_using_filesystem(
+        &self,
+        fs: &impl FileSystem,
+    ) -> Result<IndexMap<String, Config>> {
+        let Some(profiles) = &self.profiles else {
+            return Ok(IndexMap::new());
+        };
+        let mut result = IndexMap::with_capacity(profiles.len());
+
+        for (name, file_path) in profiles {
+            let config = Self::from_filesystem(Path::new(file_path), fs)
+                .with_context(|| format!("Failed to load the profile {name:?}."))?;
+            result.insert(name.clone(), config);
+        }
+
+        Ok(result)
+    }
+
+    /// Extracts the dictionaries from the configuration.
+    ///
+    /// Unlike [`Config::extract_translation`], the translations of each source are kept apart.
+    /// See [`Dictionary`] for more details.
+    pub fn extract_dictionaries(&self) -> IndexMap<String, Dictionary> {
+        self.dictionaries.clone()
+    }
+
     /// Extracts the translation from the configuration.
     pub fn extract_translation(&self) -> IndexMap<String, Vec<String>> {
         // with_capacity avoids incremental reallocations during iteration.
@@ -494,7 +721,7 @@ impl Config {
 
 #[cfg(test)]
 mod tests {
-    use crate::Config;
+    use crate::{Config, MAIN_DICTIONARY};
     use std::path::Path;
 
     #[test]
@@ -513,7 +740,8 @@ mod tests {
         );
 
         let data = conf.extract_data();
-        assert_eq!(data.keys().len(), 23);
+        assert_eq!(data.keys().len(), 21);
+        assert_eq!(conf.directory(), Path::new("./data"));
 
         // data and core not provided
         let conf = Config::from_file(Path::new("./data/blank_sample.toml")).unwrap();
@@ -565,11 +793,42 @@ mod tests {
     fn from_file_with_translation() {
         let conf = Config::from_file(Path::new("./data/config_sample.toml")).unwrap();
         let translation = conf.extract_translation();
-        assert_eq!(translation.keys().len(), 4);
+        assert_eq!(translation.keys().len(), 5);
+
+        // The translations are grouped by source.
+        let dictionaries = conf.extract_dictionaries();
+        assert_eq!(
+            dictionaries.keys().collect::<Vec<_>>(),
+            vec!["mydict", MAIN_DICTIONARY]
+        );
+        assert_eq!(dictionaries["mydict"].priority, 1);
+        assert_eq!(dictionaries["mydict"].translation.len(), 4);
+        assert_eq!(dictionaries[MAIN_DICTIONARY].priority, 0);
+        assert_eq!(
+            dictionaries[MAIN_DICTIONARY].translation["hallo"],
+            vec!["hello".to_owned()]
+        );
 
         let conf = Config::from_file(Path::new("./data/blank_sample.toml")).unwrap();
         let translation = conf.extract_translation();
         assert_eq!(translation.keys().len(), 0);
+        assert!(conf.extract_dictionaries().is_empty());
+    }
+
+    #[test]
+    fn from_file_with_profiles() {
+        let conf = Config::from_file(Path::new("./data/config_sample.toml")).unwrap();
+        let profiles = conf.extract_profiles().unwrap();
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec!["sample"]);
+        assert_eq!(profiles["sample"].extract_data().keys().len(), 21);
+
+        // profiles not provided
+        let conf = Config::from_file(Path::new("./data/blank_sample.toml")).unwrap();
+        assert_eq!(conf.extract_profiles().unwrap().keys().len(), 0);
+
+        // profile file not found
+        let conf = Config::from_file(Path::new("./data/bad_profile.toml")).unwrap();
+        assert!(conf.extract_profiles().is_err());
     }
 
     #[test]
@@ -596,7 +855,8 @@ mod tests {
         let filepath = Path::new("./data/data_sample.toml");
         let conf = Config::from_filesystem(filepath, &fs).unwrap();
 
-        assert_eq!(conf.extract_data().keys().len(), 13);
+        assert_eq!(conf.extract_data().keys().len(), 11);
+        assert_eq!(conf.extract_data_with_capitalization().keys().len(), 13);
         #[cfg(feature = "rhai")]
         assert_eq!(conf.extract_translators().unwrap().keys().len(), 0);
         assert_eq!(conf.extract_translation().keys().len(), 0);
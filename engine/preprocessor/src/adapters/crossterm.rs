@@ -0,0 +1,236 @@
+#![deny(missing_docs)]
+//! Conversion of the [`crossterm`] key events.
+//!
+//! **Note**: The terminal doesn't report the physical keys, so the code of the keyboard events
+//! is always unidentified.
+
+use crate::{Key, KeyState, KeyboardEvent, Location, Modifiers, NamedKey};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode, ModifierKeyCode,
+};
+
+/// Converts a crossterm key event into a keyboard event.
+///
+/// # Example
+///
+/// ```
+/// use afrim_preprocessor::{adapters, Key, KeyState};
+/// use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+/// use afrim_preprocessor::Modifiers;
+///
+/// let event = KeyEvent::new_with_kind(
+///     KeyCode::Char('A'),
+///     KeyModifiers::SHIFT,
+///     KeyEventKind::Release,
+/// );
+/// let event = adapters::crossterm::from_event(event);
+///
+/// assert_eq!(event.key, Key::Character("A".to_owned()));
+/// assert_eq!(event.state, KeyState::Up);
+/// assert_eq!(event.modifiers, Modifiers::SHIFT);
+/// ```
+pub fn from_event(event: KeyEvent) -> KeyboardEvent {
+    let (state, repeat) = match event.kind {
+        KeyEventKind::Press => (KeyState::Down, false),
+        KeyEventKind::Repeat => (KeyState::Down, true),
+        KeyEventKind::Release => (KeyState::Up, false),
+    };
+    let location = match event.code {
+        KeyCode::Modifier(code) => from_modifier_location(code),
+        _ if event.state.contains(KeyEventState::KEYPAD) => Location::Numpad,
+        _ => Location::Standard,
+    };
+
+    KeyboardEvent {
+        state,
+        key: from_key_code(event.code),
+        location,
+        modifiers: from_modifiers(event.modifiers, event.state),
+        repeat,
+        ..Default::default()
+    }
+}
+
+/// Converts a crossterm key code into a key.
+pub fn from_key_code(code: KeyCode) -> Key {
+    let named_key = match code {
+        KeyCode::Char(character) => return Key::Character(character.to_string()),
+        KeyCode::F(number) => return function_key(number),
+        KeyCode::Media(code) => from_media_key_code(code),
+        KeyCode::Modifier(code) => from_modifier_key_code(code),
+        KeyCode::Backspace => NamedKey::Backspace,
+        KeyCode::Enter => NamedKey::Enter,
+        KeyCode::Left => NamedKey::ArrowLeft,
+        KeyCode::Right => NamedKey::ArrowRight,
+        KeyCode::Up => NamedKey::ArrowUp,
+        KeyCode::Down => NamedKey::ArrowDown,
+        KeyCode::Home => NamedKey::Home,
+        KeyCode::End => NamedKey::End,
+        KeyCode::PageUp => NamedKey::PageUp,
+        KeyCode::PageDown => NamedKey::PageDown,
+        // The shift modifier is reported along with the key.
+        KeyCode::Tab | KeyCode::BackTab => NamedKey::Tab,
+        KeyCode::Delete => NamedKey::Delete,
+        KeyCode::Insert => NamedKey::Insert,
+        KeyCode::Esc => NamedKey::Escape,
+        KeyCode::CapsLock => NamedKey::CapsLock,
+        KeyCode::ScrollLock => NamedKey::ScrollLock,
+        KeyCode::NumLock => NamedKey::NumLock,
+        KeyCode::PrintScreen => NamedKey::PrintScreen,
+        KeyCode::Pause => NamedKey::Pause,
+        KeyCode::Menu => NamedKey::ContextMenu,
+        KeyCode::KeypadBegin => NamedKey::Clear,
+        KeyCode::Null => NamedKey::Unidentified,
+    };
+
+    Key::Named(named_key)
+}
+
+// Converts a function key number into a key.
+fn function_key(number: u8) -> Key {
+    format!("F{number}")
+        .parse()
+        .unwrap_or(Key::Named(NamedKey::Unidentified))
+}
+
+// Converts a crossterm media key code into a named key.
+fn from_media_key_code(code: MediaKeyCode) -> NamedKey {
+    match code {
+        MediaKeyCode::Play => NamedKey::MediaPlay,
+        MediaKeyCode::Pause => NamedKey::MediaPause,
+        MediaKeyCode::PlayPause => NamedKey::MediaPlayPause,
+        MediaKeyCode::Reverse => NamedKey::MediaRewind,
+        MediaKeyCode::Stop => NamedKey::MediaStop,
+        MediaKeyCode::FastForward => NamedKey::MediaFastForward,
+        MediaKeyCode::Rewind => NamedKey::MediaRewind,
+        MediaKeyCode::TrackNext => NamedKey::MediaTrackNext,
+        MediaKeyCode::TrackPrevious => NamedKey::MediaTrackPrevious,
+        MediaKeyCode::Record => NamedKey::MediaRecord,
+        MediaKeyCode::LowerVolume => NamedKey::AudioVolumeDown,
+        MediaKeyCode::RaiseVolume => NamedKey::AudioVolumeUp,
+        MediaKeyCode::MuteVolume => NamedKey::AudioVolumeMute,
+    }
+}
+
+// Converts a crossterm modifier key code into a named key.
+fn from_modifier_key_code(code: ModifierKeyCode) -> NamedKey {
+    match code {
+        ModifierKeyCode::LeftShift | ModifierKeyCode::RightShift => NamedKey::Shift,
+        ModifierKeyCode::LeftControl | ModifierKeyCode::RightControl => NamedKey::Control,
+        ModifierKeyCode::LeftAlt | ModifierKeyCode::RightAlt => NamedKey::Alt,
+        ModifierKeyCode::LeftSuper
+        | ModifierKeyCode::RightSuper
+        | ModifierKeyCode::LeftHyper
+        | ModifierKeyCode::RightHyper
+        | ModifierKeyCode::LeftMeta
+        | ModifierKeyCode::RightMeta => NamedKey::Meta,
+        ModifierKeyCode::IsoLevel3Shift => NamedKey::AltGraph,
+        ModifierKeyCode::IsoLevel5Shift => NamedKey::Unidentified,
+    }
+}
+
+// Returns the location of a crossterm modifier key.
+fn from_modifier_location(code: ModifierKeyCode) -> Location {
+    match code {
+        ModifierKeyCode::LeftShift
+        | ModifierKeyCode::LeftControl
+        | ModifierKeyCode::LeftAlt
+        | ModifierKeyCode::LeftSuper
+        | ModifierKeyCode::LeftHyper
+        | ModifierKeyCode::LeftMeta => Location::Left,
+        ModifierKeyCode::RightShift
+        | ModifierKeyCode::RightControl
+        | ModifierKeyCode::RightAlt
+        | ModifierKeyCode::RightSuper
+        | ModifierKeyCode::RightHyper
+        | ModifierKeyCode::RightMeta => Location::Right,
+        ModifierKeyCode::IsoLevel3Shift | ModifierKeyCode::IsoLevel5Shift => Location::Standard,
+    }
+}
+
+/// Converts the crossterm modifiers and lock states into modifiers.
+pub fn from_modifiers(modifiers: KeyModifiers, state: KeyEventState) -> Modifiers {
+    let mut result = Modifiers::empty();
+
+    result.set(Modifiers::SHIFT, modifiers.contains(KeyModifiers::SHIFT));
+    result.set(
+        Modifiers::CONTROL,
+        modifiers.contains(KeyModifiers::CONTROL),
+    );
+    result.set(Modifiers::ALT, modifiers.contains(KeyModifiers::ALT));
+    result.set(
+        Modifiers::META,
+        modifiers.intersects(KeyModifiers::SUPER | KeyModifiers::HYPER | KeyModifiers::META),
+    );
+    result.set(
+        Modifiers::CAPS_LOCK,
+        state.contains(KeyEventState::CAPS_LOCK),
+    );
+    result.set(Modifiers::NUM_LOCK, state.contains(KeyEventState::NUM_LOCK));
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_from_event() {
+        use super::*;
+
+        // Characters.
+        let event = from_event(KeyEvent::new(KeyCode::Char('ŋ'), KeyModifiers::NONE));
+        assert_eq!(event.key, Key::Character("ŋ".to_owned()));
+        assert_eq!(event.state, KeyState::Down);
+        assert!(!event.repeat);
+
+        // Named keys.
+        [
+            (KeyCode::Esc, Key::Named(NamedKey::Escape)),
+            (KeyCode::Enter, Key::Named(NamedKey::Enter)),
+            (KeyCode::Tab, Key::Named(NamedKey::Tab)),
+            (KeyCode::BackTab, Key::Named(NamedKey::Tab)),
+            (KeyCode::Backspace, Key::Named(NamedKey::Backspace)),
+            (KeyCode::Left, Key::Named(NamedKey::ArrowLeft)),
+            (KeyCode::Down, Key::Named(NamedKey::ArrowDown)),
+            (KeyCode::F(1), Key::Named(NamedKey::F1)),
+            (KeyCode::F(12), Key::Named(NamedKey::F12)),
+            (KeyCode::F(35), Key::Named(NamedKey::F35)),
+            (KeyCode::F(36), Key::Named(NamedKey::Unidentified)),
+            (
+                KeyCode::Media(MediaKeyCode::PlayPause),
+                Key::Named(NamedKey::MediaPlayPause),
+            ),
+            (KeyCode::Null, Key::Named(NamedKey::Unidentified)),
+        ]
+        .into_iter()
+        .for_each(|(code, key)| {
+            assert_eq!(from_event(KeyEvent::new(code, KeyModifiers::NONE)).key, key)
+        });
+
+        // Modifier keys.
+        let event = from_event(KeyEvent::new_with_kind(
+            KeyCode::Modifier(ModifierKeyCode::RightShift),
+            KeyModifiers::SHIFT,
+            KeyEventKind::Release,
+        ));
+        assert_eq!(event.key, Key::Named(NamedKey::Shift));
+        assert_eq!(event.location, Location::Right);
+        assert_eq!(event.state, KeyState::Up);
+        assert_eq!(event.modifiers, Modifiers::SHIFT);
+
+        // Repeated keypad keys.
+        let event = from_event(KeyEvent::new_with_kind_and_state(
+            KeyCode::Char('5'),
+            KeyModifiers::SUPER | KeyModifiers::CONTROL,
+            KeyEventKind::Repeat,
+            KeyEventState::KEYPAD | KeyEventState::NUM_LOCK,
+        ));
+        assert_eq!(event.location, Location::Numpad);
+        assert_eq!(event.state, KeyState::Down);
+        assert!(event.repeat);
+        assert_eq!(
+            event.modifiers,
+            Modifiers::META | Modifiers::CONTROL | Modifiers::NUM_LOCK
+        );
+    }
+}
@@ -0,0 +1,15 @@
+#![deny(missing_docs)]
+//! Convert the keyboard events of external libraries into keyboard events understood by the
+//! preprocessor.
+//!
+//! Each adapter is available behind a feature of the same name.
+//! - crossterm: Conversion of the [`crossterm`](::crossterm) key events.
+//! - winit: Conversion of the [`winit`](::winit) key events.
+//! - x11: Conversion of the X11 keysyms, along with their modifier state.
+
+#[cfg(feature = "crossterm")]
+pub mod crossterm;
+#[cfg(feature = "winit")]
+pub mod winit;
+#[cfg(feature = "x11")]
+pub mod x11;
@@ -0,0 +1,175 @@
+#![deny(missing_docs)]
+//! Conversion of the [`winit`] key events.
+//!
+//! The keys and codes of winit follow the same specification as the keyboard events, so most of
+//! them are converted by name.
+
+use crate::{Code, Key, KeyState, KeyboardEvent, Location, Modifiers, NamedKey};
+use winit::{
+    event::{ElementState, KeyEvent},
+    keyboard::{self, KeyCode, KeyLocation, ModifiersState, PhysicalKey},
+};
+
+/// Converts a winit key event into a keyboard event.
+///
+/// The modifiers are those reported by the last `WindowEvent::ModifiersChanged`.
+pub fn from_event(event: &KeyEvent, modifiers: ModifiersState) -> KeyboardEvent {
+    let state = match event.state {
+        ElementState::Pressed => KeyState::Down,
+        ElementState::Released => KeyState::Up,
+    };
+
+    KeyboardEvent {
+        state,
+        key: from_key(&event.logical_key),
+        code: from_physical_key(event.physical_key),
+        location: from_location(event.location),
+        modifiers: from_modifiers(modifiers),
+        repeat: event.repeat,
+        ..Default::default()
+    }
+}
+
+/// Converts a winit logical key into a key.
+///
+/// # Example
+///
+/// ```
+/// use afrim_preprocessor::{adapters, Key, NamedKey};
+/// use winit::keyboard;
+///
+/// assert_eq!(
+///     adapters::winit::from_key(&keyboard::Key::Character("ɑ".into())),
+///     Key::Character("ɑ".to_owned())
+/// );
+/// assert_eq!(
+///     adapters::winit::from_key(&keyboard::Key::Named(keyboard::NamedKey::Escape)),
+///     Key::Named(NamedKey::Escape)
+/// );
+/// ```
+pub fn from_key(key: &keyboard::Key) -> Key {
+    match key {
+        keyboard::Key::Character(character) => Key::Character(character.to_string()),
+        keyboard::Key::Named(named_key) => from_named_key(*named_key),
+        keyboard::Key::Dead(_) => Key::Named(NamedKey::Dead),
+        keyboard::Key::Unidentified(_) => Key::Named(NamedKey::Unidentified),
+    }
+}
+
+// Converts a winit named key into a key.
+fn from_named_key(named_key: keyboard::NamedKey) -> Key {
+    match named_key {
+        keyboard::NamedKey::Space => Key::Character(" ".to_owned()),
+        // The Super key of winit is the Meta key of the specification.
+        keyboard::NamedKey::Super | keyboard::NamedKey::Meta | keyboard::NamedKey::Hyper => {
+            Key::Named(NamedKey::Meta)
+        }
+        named_key => format!("{named_key:?}")
+            .parse()
+            .unwrap_or(Key::Named(NamedKey::Unidentified)),
+    }
+}
+
+/// Converts a winit physical key into a code.
+pub fn from_physical_key(physical_key: PhysicalKey) -> Code {
+    match physical_key {
+        PhysicalKey::Code(KeyCode::SuperLeft) => Code::MetaLeft,
+        PhysicalKey::Code(KeyCode::SuperRight) => Code::MetaRight,
+        PhysicalKey::Code(code) => format!("{code:?}").parse().unwrap_or(Code::Unidentified),
+        PhysicalKey::Unidentified(_) => Code::Unidentified,
+    }
+}
+
+/// Converts a winit key location into a location.
+pub fn from_location(location: KeyLocation) -> Location {
+    match location {
+        KeyLocation::Standard => Location::Standard,
+        KeyLocation::Left => Location::Left,
+        KeyLocation::Right => Location::Right,
+        KeyLocation::Numpad => Location::Numpad,
+    }
+}
+
+/// Converts the winit modifiers into modifiers.
+pub fn from_modifiers(modifiers: ModifiersState) -> Modifiers {
+    let mut result = Modifiers::empty();
+
+    result.set(Modifiers::SHIFT, modifiers.shift_key());
+    result.set(Modifiers::CONTROL, modifiers.control_key());
+    result.set(Modifiers::ALT, modifiers.alt_key());
+    result.set(Modifiers::META, modifiers.super_key());
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_from_key() {
+        use super::*;
+
+        [
+            (keyboard::NamedKey::Enter, Key::Named(NamedKey::Enter)),
+            (keyboard::NamedKey::Tab, Key::Named(NamedKey::Tab)),
+            (keyboard::NamedKey::Escape, Key::Named(NamedKey::Escape)),
+            (
+                keyboard::NamedKey::Backspace,
+                Key::Named(NamedKey::Backspace),
+            ),
+            (keyboard::NamedKey::ArrowUp, Key::Named(NamedKey::ArrowUp)),
+            (keyboard::NamedKey::F1, Key::Named(NamedKey::F1)),
+            (keyboard::NamedKey::F35, Key::Named(NamedKey::F35)),
+            (keyboard::NamedKey::Super, Key::Named(NamedKey::Meta)),
+            (keyboard::NamedKey::AltGraph, Key::Named(NamedKey::AltGraph)),
+            (
+                keyboard::NamedKey::MediaPlayPause,
+                Key::Named(NamedKey::MediaPlayPause),
+            ),
+            (keyboard::NamedKey::Space, Key::Character(" ".to_owned())),
+        ]
+        .into_iter()
+        .for_each(|(named_key, key)| assert_eq!(from_key(&keyboard::Key::Named(named_key)), key));
+
+        assert_eq!(
+            from_key(&keyboard::Key::Dead(Some('^'))),
+            Key::Named(NamedKey::Dead)
+        );
+        assert_eq!(
+            from_key(&keyboard::Key::Unidentified(
+                keyboard::NativeKey::Unidentified
+            )),
+            Key::Named(NamedKey::Unidentified)
+        );
+    }
+
+    #[test]
+    fn test_from_physical_key() {
+        use super::*;
+
+        [
+            (KeyCode::KeyQ, Code::KeyQ),
+            (KeyCode::Digit1, Code::Digit1),
+            (KeyCode::Semicolon, Code::Semicolon),
+            (KeyCode::NumpadAdd, Code::NumpadAdd),
+            (KeyCode::SuperLeft, Code::MetaLeft),
+            (KeyCode::F24, Code::F24),
+            (KeyCode::AudioVolumeUp, Code::AudioVolumeUp),
+        ]
+        .into_iter()
+        .for_each(|(key_code, code)| {
+            assert_eq!(from_physical_key(PhysicalKey::Code(key_code)), code)
+        });
+
+        assert_eq!(
+            from_physical_key(PhysicalKey::Unidentified(
+                keyboard::NativeKeyCode::Unidentified
+            )),
+            Code::Unidentified
+        );
+        assert_eq!(from_location(KeyLocation::Numpad), Location::Numpad);
+        assert_eq!(
+            from_modifiers(ModifiersState::SHIFT | ModifiersState::SUPER),
+            Modifiers::SHIFT | Modifiers::META
+        );
+    }
+}
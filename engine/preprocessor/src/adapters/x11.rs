@@ -0,0 +1,190 @@
+#![deny(missing_docs)]
+//! Conversion of the X11 keysyms.
+//!
+//! An X11 key event reports a keysym and a state, which is the mask of the modifiers active.
+//! The keysym is expected to be already resolved according to this state (e.g. `A` instead of
+//! `a` when the shift key is pressed).
+
+use crate::{Key, KeyState, KeyboardEvent, Location, Modifiers, NamedKey};
+use xkeysym::{key, Keysym};
+
+// The masks of the modifiers in the state of an X11 key event.
+const SHIFT_MASK: u16 = 1 << 0;
+const LOCK_MASK: u16 = 1 << 1;
+const CONTROL_MASK: u16 = 1 << 2;
+const MOD1_MASK: u16 = 1 << 3;
+const MOD2_MASK: u16 = 1 << 4;
+const MOD4_MASK: u16 = 1 << 6;
+const MOD5_MASK: u16 = 1 << 7;
+
+/// Converts an X11 keysym and its state into a keyboard event.
+///
+/// # Example
+///
+/// ```
+/// use afrim_preprocessor::{adapters, Key, KeyState, NamedKey};
+/// use afrim_preprocessor::Modifiers;
+/// use xkeysym::Keysym;
+///
+/// // Shift + a
+/// let event = adapters::x11::from_keysym(Keysym::A, 0x1, KeyState::Down);
+/// assert_eq!(event.key, Key::Character("A".to_owned()));
+/// assert_eq!(event.modifiers, Modifiers::SHIFT);
+///
+/// let event = adapters::x11::from_keysym(Keysym::Return, 0x0, KeyState::Up);
+/// assert_eq!(event.key, Key::Named(NamedKey::Enter));
+/// assert_eq!(event.state, KeyState::Up);
+/// ```
+pub fn from_keysym(keysym: Keysym, state: u16, key_state: KeyState) -> KeyboardEvent {
+    KeyboardEvent {
+        state: key_state,
+        key: from_key(keysym),
+        location: from_location(keysym),
+        modifiers: from_state(state),
+        ..Default::default()
+    }
+}
+
+/// Converts an X11 keysym into a key.
+pub fn from_key(keysym: Keysym) -> Key {
+    let named_key = match keysym.raw() {
+        key::F1..=key::F35 => {
+            let number = keysym.raw() - key::F1 + 1;
+
+            return format!("F{number}")
+                .parse()
+                .unwrap_or(Key::Named(NamedKey::Unidentified));
+        }
+        key::dead_grave..=key::dead_longsolidusoverlay => NamedKey::Dead,
+        key::BackSpace => NamedKey::Backspace,
+        key::Tab | key::ISO_Left_Tab | key::KP_Tab => NamedKey::Tab,
+        key::Clear | key::Begin | key::KP_Begin => NamedKey::Clear,
+        key::Return | key::KP_Enter => NamedKey::Enter,
+        key::Pause | key::Break => NamedKey::Pause,
+        key::Scroll_Lock => NamedKey::ScrollLock,
+        key::Escape => NamedKey::Escape,
+        key::Delete | key::KP_Delete => NamedKey::Delete,
+        key::Home | key::KP_Home => NamedKey::Home,
+        key::Left | key::KP_Left => NamedKey::ArrowLeft,
+        key::Up | key::KP_Up => NamedKey::ArrowUp,
+        key::Right | key::KP_Right => NamedKey::ArrowRight,
+        key::Down | key::KP_Down => NamedKey::ArrowDown,
+        key::Prior | key::KP_Prior => NamedKey::PageUp,
+        key::Next | key::KP_Next => NamedKey::PageDown,
+        key::End | key::KP_End => NamedKey::End,
+        key::Insert | key::KP_Insert => NamedKey::Insert,
+        key::Select => NamedKey::Select,
+        key::Print | key::Sys_Req => NamedKey::PrintScreen,
+        key::Execute => NamedKey::Execute,
+        key::Undo => NamedKey::Undo,
+        key::Redo => NamedKey::Redo,
+        key::Menu => NamedKey::ContextMenu,
+        key::Find => NamedKey::Find,
+        key::Cancel => NamedKey::Cancel,
+        key::Help => NamedKey::Help,
+        key::Mode_switch => NamedKey::ModeChange,
+        key::Num_Lock => NamedKey::NumLock,
+        key::Caps_Lock | key::Shift_Lock => NamedKey::CapsLock,
+        key::Shift_L | key::Shift_R => NamedKey::Shift,
+        key::Control_L | key::Control_R => NamedKey::Control,
+        key::Alt_L | key::Alt_R => NamedKey::Alt,
+        key::Meta_L | key::Meta_R | key::Super_L | key::Super_R | key::Hyper_L | key::Hyper_R => {
+            NamedKey::Meta
+        }
+        key::ISO_Level3_Shift => NamedKey::AltGraph,
+        key::XF86_AudioLowerVolume => NamedKey::AudioVolumeDown,
+        key::XF86_AudioRaiseVolume => NamedKey::AudioVolumeUp,
+        key::XF86_AudioMute => NamedKey::AudioVolumeMute,
+        key::XF86_AudioPlay => NamedKey::MediaPlay,
+        key::XF86_AudioPause => NamedKey::MediaPause,
+        key::XF86_AudioStop => NamedKey::MediaStop,
+        key::XF86_AudioPrev => NamedKey::MediaTrackPrevious,
+        key::XF86_AudioNext => NamedKey::MediaTrackNext,
+        key::XF86_AudioRecord => NamedKey::MediaRecord,
+        key::XF86_AudioRewind => NamedKey::MediaRewind,
+        key::XF86_AudioForward => NamedKey::MediaFastForward,
+        _ => {
+            return keysym
+                .key_char()
+                .filter(|character| !character.is_control())
+                .map(|character| Key::Character(character.to_string()))
+                .unwrap_or(Key::Named(NamedKey::Unidentified))
+        }
+    };
+
+    Key::Named(named_key)
+}
+
+/// Returns the location of the key of an X11 keysym.
+pub fn from_location(keysym: Keysym) -> Location {
+    match keysym.raw() {
+        key::Shift_L | key::Control_L | key::Alt_L | key::Meta_L | key::Super_L | key::Hyper_L => {
+            Location::Left
+        }
+        key::Shift_R | key::Control_R | key::Alt_R | key::Meta_R | key::Super_R | key::Hyper_R => {
+            Location::Right
+        }
+        _ if keysym.is_keypad_key() => Location::Numpad,
+        _ => Location::Standard,
+    }
+}
+
+/// Converts the state of an X11 key event into modifiers.
+///
+/// The default mapping of the modifiers is assumed (i.e. Mod1 for Alt, Mod2 for NumLock,
+/// Mod4 for Super and Mod5 for AltGr).
+pub fn from_state(state: u16) -> Modifiers {
+    let mut result = Modifiers::empty();
+
+    result.set(Modifiers::SHIFT, state & SHIFT_MASK != 0);
+    result.set(Modifiers::CAPS_LOCK, state & LOCK_MASK != 0);
+    result.set(Modifiers::CONTROL, state & CONTROL_MASK != 0);
+    result.set(Modifiers::ALT, state & MOD1_MASK != 0);
+    result.set(Modifiers::NUM_LOCK, state & MOD2_MASK != 0);
+    result.set(Modifiers::META, state & MOD4_MASK != 0);
+    result.set(Modifiers::ALT_GRAPH, state & MOD5_MASK != 0);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_from_keysym() {
+        use super::*;
+
+        [
+            (Keysym::a, Key::Character("a".to_owned())),
+            (Keysym::space, Key::Character(" ".to_owned())),
+            (Keysym::eng, Key::Character("ŋ".to_owned())),
+            (Keysym::KP_5, Key::Character("5".to_owned())),
+            (Keysym::Escape, Key::Named(NamedKey::Escape)),
+            (Keysym::Return, Key::Named(NamedKey::Enter)),
+            (Keysym::KP_Enter, Key::Named(NamedKey::Enter)),
+            (Keysym::ISO_Left_Tab, Key::Named(NamedKey::Tab)),
+            (Keysym::BackSpace, Key::Named(NamedKey::Backspace)),
+            (Keysym::Left, Key::Named(NamedKey::ArrowLeft)),
+            (Keysym::KP_Down, Key::Named(NamedKey::ArrowDown)),
+            (Keysym::F1, Key::Named(NamedKey::F1)),
+            (Keysym::F12, Key::Named(NamedKey::F12)),
+            (Keysym::F35, Key::Named(NamedKey::F35)),
+            (Keysym::Super_L, Key::Named(NamedKey::Meta)),
+            (Keysym::ISO_Level3_Shift, Key::Named(NamedKey::AltGraph)),
+            (Keysym::dead_circumflex, Key::Named(NamedKey::Dead)),
+            (Keysym::XF86_AudioPlay, Key::Named(NamedKey::MediaPlay)),
+            (Keysym::NoSymbol, Key::Named(NamedKey::Unidentified)),
+        ]
+        .into_iter()
+        .for_each(|(keysym, key)| assert_eq!(from_key(keysym), key));
+
+        let event = from_keysym(Keysym::Shift_R, SHIFT_MASK | MOD2_MASK, KeyState::Up);
+        assert_eq!(event.key, Key::Named(NamedKey::Shift));
+        assert_eq!(event.location, Location::Right);
+        assert_eq!(event.state, KeyState::Up);
+        assert_eq!(event.modifiers, Modifiers::SHIFT | Modifiers::NUM_LOCK);
+
+        let event = from_keysym(Keysym::KP_1, MOD5_MASK | LOCK_MASK, KeyState::Down);
+        assert_eq!(event.location, Location::Numpad);
+        assert_eq!(event.modifiers, Modifiers::ALT_GRAPH | Modifiers::CAPS_LOCK);
+    }
+}
@@ -0,0 +1,312 @@
+#![deny(missing_docs)]
+//! Record and replay the activity of the preprocessor.
+//!
+//! A journal keeps track of the keyboard events received by the preprocessor, the actions
+//! requested by its user (commitments, reverts and selections of alternates), and the commands emitted in response.
+//! The actions unknown to the preprocessor (e.g. a switch of input method) can be recorded too.
+//! Replaying a journal through a fresh preprocessor makes it possible to reproduce a session
+//! and to detect the first point where the behavior differs.
+
+use crate::{Command, KeyboardEvent, Preprocessor};
+use std::{error, fmt, time::Duration};
+
+/// An entry of the journal.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Entry {
+    /// A keyboard event received, with its timestamp if any.
+    Event {
+        /// The keyboard event.
+        event: KeyboardEvent,
+        /// The timestamp of the keyboard event.
+        timestamp: Option<Duration>,
+    },
+    /// A text committed.
+    Commit(String),
+    /// A revert of the last conversion.
+    Revert,
+    /// A selection of an alternate, identified by the characters remaining to reach it.
+    Alternate(String),
+    /// An action unknown to the preprocessor, encoded by the application which recorded it.
+    Action(String),
+    /// A command emitted.
+    Command(Command),
+}
+
+/// A journal of the activity of the preprocessor.
+///
+/// # Example
+///
+/// ```
+/// use afrim_preprocessor::{utils, Command, Entry, Preprocessor};
+/// use keyboard_types::webdriver::{self, Event};
+/// use std::rc::Rc;
+///
+/// // We prepare the memory.
+/// let data = utils::load_data("cc ç");
+/// let text_buffer = utils::build_map(data);
+/// let memory = Rc::new(text_buffer);
+///
+/// // We record a session.
+/// let mut preprocessor = Preprocessor::new(memory.clone(), 8);
+/// preprocessor.start_recording();
+///
+/// webdriver::send_keys("cc")
+///     .into_iter()
+///     .for_each(|event| {
+///         match event {
+///             Event::Keyboard(event) => preprocessor.process(event),
+///             _ => unimplemented!(),
+///         };
+///     });
+/// while preprocessor.pop_queue().is_some() {}
+///
+/// let journal = preprocessor.stop_recording().unwrap();
+/// assert!(journal
+///     .entries()
+///     .contains(&Entry::Command(Command::CommitText("ç".to_owned()))));
+///
+/// // We replay it through a fresh preprocessor.
+/// let mut preprocessor = Preprocessor::new(memory, 8);
+/// assert_eq!(journal.replay(&mut preprocessor), Ok(()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Journal {
+    entries: Vec<Entry>,
+}
+
+impl Journal {
+    /// Initializes an empty journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry at the end of the journal.
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns the entries of the journal.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Removes the entries of the journal and returns them.
+    ///
+    /// Useful to save the journal progressively.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Entry> {
+        self.entries.drain(..)
+    }
+
+    /// Replays the journal through a preprocessor.
+    ///
+    /// The keyboard events are processed with [`Preprocessor::process`] or
+    /// [`Preprocessor::process_with_timestamp`].
+    /// See [`Journal::replay_with`] for more details.
+    pub fn replay(&self, preprocessor: &mut Preprocessor) -> Result<(), Divergence> {
+        self.replay_with(preprocessor, |preprocessor, entry| match entry {
+            Entry::Event {
+                event,
+                timestamp: Some(timestamp),
+            } => {
+                preprocessor.process_with_timestamp(event.clone(), *timestamp);
+            }
+            Entry::Event { event, .. } => {
+                preprocessor.process(event.clone());
+            }
+            _ => (),
+        })
+    }
+
+    /// Replays the journal through a preprocessor, using a custom processing of the keyboard
+    /// events and of the actions.
+    ///
+    /// Useful when the keyboard events were processed along with other components (e.g. a
+    /// translator committing the predicates), or when the application recorded its own actions.
+    /// The processing is called with the [`Entry::Event`] and [`Entry::Action`] entries.
+    ///
+    /// The commitments, reverts and selections recorded are applied if the processing didn't
+    /// already generate them, and the commands are popped from the queue of the preprocessor.
+    /// Returns the first point where the activity of the preprocessor differs from the journal.
+    pub fn replay_with<F>(
+        &self,
+        preprocessor: &mut Preprocessor,
+        mut process: F,
+    ) -> Result<(), Divergence>
+    where
+        F: FnMut(&mut Preprocessor, &Entry),
+    {
+        let recorded = |preprocessor: &Preprocessor| {
+            preprocessor
+                .journal()
+                .map(|journal| journal.entries.len())
+                .unwrap_or_default()
+        };
+
+        preprocessor.start_recording();
+
+        for (position, entry) in self.entries.iter().enumerate() {
+            match entry {
+                Entry::Event { .. } => {
+                    process(preprocessor, entry);
+                }
+                Entry::Action(_) => {
+                    process(preprocessor, entry);
+
+                    if recorded(preprocessor) <= position {
+                        if let Some(journal) = preprocessor.journal_mut() {
+                            journal.push(entry.to_owned());
+                        }
+                    }
+                }
+                Entry::Commit(text) if recorded(preprocessor) <= position => {
+                    preprocessor.commit(text.to_owned());
+                }
+                Entry::Revert if recorded(preprocessor) <= position => {
+                    preprocessor.revert();
+                }
+                Entry::Alternate(remaining) if recorded(preprocessor) <= position => {
+                    preprocessor.select_alternate(remaining);
+                }
+                Entry::Command(_) if recorded(preprocessor) <= position => {
+                    preprocessor.pop_queue();
+                }
+                _ => (),
+            }
+
+            let found = preprocessor
+                .journal()
+                .and_then(|journal| journal.entries.get(position));
+
+            if found != Some(entry) {
+                let found = found.cloned();
+                preprocessor.stop_recording();
+
+                return Err(Divergence {
+                    position,
+                    expected: Some(entry.to_owned()),
+                    found,
+                });
+            }
+        }
+
+        let journal = preprocessor.stop_recording().unwrap_or_default();
+
+        match journal.entries.get(self.entries.len()) {
+            Some(entry) => Err(Divergence {
+                position: self.entries.len(),
+                expected: None,
+                found: Some(entry.to_owned()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The first difference between a journal and its replay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The position of the entry in the journal.
+    pub position: usize,
+    /// The entry recorded.
+    pub expected: Option<Entry>,
+    /// The entry generated during the replay.
+    pub found: Option<Entry>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "divergence at entry {}: expected {:?}, found {:?}",
+            self.position, self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for Divergence {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_replay() {
+        use crate::{utils, Command, Entry, Journal, Preprocessor};
+        use keyboard_types::{
+            webdriver::{self, Event},
+            Key::*,
+            KeyboardEvent,
+        };
+        use std::rc::Rc;
+
+        let data = utils::load_data("af ɑ\nc_ ç");
+        let memory = Rc::new(utils::build_map(data));
+
+        let mut preprocessor = Preprocessor::new(memory.clone(), 8);
+        preprocessor.start_recording();
+        webdriver::send_keys("afc_")
+            .into_iter()
+            .for_each(|event| match event {
+                Event::Keyboard(event) => {
+                    preprocessor.process(event);
+                    while preprocessor.pop_queue().is_some() {}
+                }
+                _ => unimplemented!(),
+            });
+        preprocessor.revert();
+        preprocessor.commit("ok".to_owned());
+        while preprocessor.pop_queue().is_some() {}
+        let journal = preprocessor.stop_recording().unwrap();
+
+        assert!(matches!(
+            &journal.entries()[0],
+            Entry::Event { event, timestamp: None } if event.key == Character("a".to_owned())
+        ));
+        assert!(journal.entries().contains(&Entry::Revert));
+        assert!(journal.entries().contains(&Entry::Commit("ok".to_owned())));
+        assert_eq!(
+            journal.entries().last(),
+            Some(&Entry::Command(Command::Resume))
+        );
+
+        // Identical replay.
+        let mut preprocessor = Preprocessor::new(memory.clone(), 8);
+        assert_eq!(journal.replay(&mut preprocessor), Ok(()));
+        assert!(preprocessor.journal().is_none());
+
+        // Replay with a different memory.
+        let data = utils::load_data("af ɑ");
+        let mut preprocessor = Preprocessor::new(Rc::new(utils::build_map(data)), 8);
+        let divergence = journal.replay(&mut preprocessor).unwrap_err();
+        assert!(matches!(divergence.expected, Some(Entry::Command(_))));
+        assert_ne!(divergence.expected, divergence.found);
+
+        // Replay with a custom processing.
+        let mut journal = Journal::new();
+        journal.push(Entry::Event {
+            event: KeyboardEvent {
+                key: Character("x".to_owned()),
+                ..Default::default()
+            },
+            timestamp: None,
+        });
+        journal.push(Entry::Commit("y".to_owned()));
+        journal.push(Entry::Action("switch".to_owned()));
+        let mut preprocessor = Preprocessor::new(memory, 8);
+        let mut actions = vec![];
+        assert_eq!(
+            journal.replay_with(&mut preprocessor, |preprocessor, entry| match entry {
+                Entry::Event { event, .. } => {
+                    preprocessor.process(event.clone());
+                    preprocessor.commit("y".to_owned());
+                }
+                Entry::Action(action) => actions.push(action.to_owned()),
+                _ => unreachable!(),
+            }),
+            Ok(())
+        );
+        assert_eq!(actions, vec!["switch".to_owned()]);
+        assert_eq!(journal.drain().count(), 3);
+        assert!(journal.entries().is_empty());
+    }
+}
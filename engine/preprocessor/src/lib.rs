@@ -65,19 +65,50 @@
 //! **Note**: When dealing with non latin languages. The `inhibit` feature allows for the removal of
 //! unwanted characters typically latin characters, as much as posssible.
 
+#[cfg(any(feature = "crossterm", feature = "winit", feature = "x11"))]
+pub mod adapters;
+mod journal;
 mod message;
+mod observer;
+mod physical;
+mod state;
 
+pub use crate::journal::{Divergence, Entry, Journal};
 pub use crate::message::Command;
-pub use afrim_memory::utils;
-use afrim_memory::{Cursor, Node};
-pub use keyboard_types::{Key, KeyState, KeyboardEvent, NamedKey};
-use std::{collections::VecDeque, rc::Rc};
+use crate::observer::Observers;
+pub use crate::observer::{Notification, Observer};
+pub use crate::state::State;
+use afrim_memory::Cursor;
+pub use afrim_memory::{utils, CaseRules, Node};
+pub use keyboard_types::{Code, Key, KeyState, KeyboardEvent, Location, Modifiers, NamedKey};
+use std::{collections::VecDeque, rc::Rc, time::Duration};
 
 /// The main structure of the preprocessor.
 #[derive(Debug)]
 pub struct Preprocessor {
     cursor: Cursor,
     queue: VecDeque<Command>,
+    escape_key: Option<char>,
+    // Whether the next character should be kept as it is.
+    // The boolean indicates if the escape key is still displayed.
+    literal: Option<bool>,
+    // Whether the characters should be kept as they are until the sequence ends.
+    verbatim: bool,
+    timeout: Option<Duration>,
+    last_timestamp: Option<Duration>,
+    journal: Option<Journal>,
+    separators: Vec<char>,
+    // The text to commit when a separator ends the current word.
+    candidate: Option<String>,
+    history: VecDeque<String>,
+    history_size: usize,
+    observers: Observers,
+    physical_keys: bool,
+    // Whether a shift key is pressed.
+    shift: bool,
+    alternates: bool,
+    // Whether the alternates of the held key have been offered.
+    holding: bool,
 }
 
 impl Preprocessor {
@@ -111,106 +142,135 @@ impl Preprocessor {
         let cursor = Cursor::new(memory, buffer_size);
         let queue = VecDeque::with_capacity(15);
 
-        Self { cursor, queue }
-    }
-
-    // Cancel the previous operation.
-    fn rollback(&mut self) -> bool {
-        if let Some(out) = self.cursor.undo() {
-            #[cfg(feature = "inhibit")]
-            let start = 0;
-            #[cfg(not(feature = "inhibit"))]
-            let start = 1;
-            let end = out.chars().count();
-
-            (start..end).for_each(|_| self.queue.push_back(Command::Delete));
-
-            // Clear the remaining code
-            while let (None, 1.., ..) = self.cursor.state() {
-                self.cursor.undo();
-            }
-
-            if let (Some(_in), ..) = self.cursor.state() {
-                self.queue.push_back(Command::CommitText(_in));
-            }
-
-            true
-        } else {
-            self.cursor.resume();
-
-            false
+        Self {
+            cursor,
+            queue,
+            escape_key: None,
+            literal: None,
+            verbatim: false,
+            timeout: None,
+            last_timestamp: None,
+            journal: None,
+            separators: vec![],
+            candidate: None,
+            history: VecDeque::new(),
+            history_size: 0,
+            observers: Observers::default(),
+            physical_keys: false,
+            shift: false,
+            alternates: false,
+            holding: false,
         }
     }
 
-    // Cancel the previous operation.
-    //
-    // Note that it handles the delete by itself.
-    #[cfg(not(feature = "inhibit"))]
-    fn hard_rollback(&mut self) -> bool {
-        self.queue.push_back(Command::Delete);
-        self.rollback()
-    }
-
-    // Cancel the previous opeartion.
-    //
-    // Note that the delete is supposed already executed.
-    fn soft_rollback(&mut self) -> bool {
-        self.queue.push_back(Command::CleanDelete);
-        self.rollback()
+    /// Switches the memory of the preprocessor.
+    ///
+    /// Useful to change the input method at runtime.
+    ///
+    /// **Note**: The current input is discarded, but the commands not yet popped from the queue
+    /// are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memories.
+    /// let bamun = Rc::new(utils::build_map(utils::load_data("af ɑ")));
+    /// let ewondo = Rc::new(utils::build_map(utils::load_data("ee ə")));
+    ///
+    /// let mut preprocessor = Preprocessor::new(bamun, 8);
+    /// let process = |preprocessor: &mut Preprocessor, input| {
+    ///     webdriver::send_keys(input)
+    ///         .into_iter()
+    ///         .for_each(|event| {
+    ///             match event {
+    ///                 Event::Keyboard(event) => preprocessor.process(event),
+    ///                 _ => unimplemented!(),
+    ///             };
+    ///         });
+    /// };
+    ///
+    /// // We start a sequence, then we switch the memory.
+    /// process(&mut preprocessor, "e");
+    /// preprocessor.set_memory(ewondo);
+    /// assert_eq!(preprocessor.get_input(), "".to_owned());
+    ///
+    /// process(&mut preprocessor, "ee");
+    /// preprocessor.clear_queue();
+    /// assert_eq!(preprocessor.get_input(), "ee".to_owned());
+    /// ```
+    pub fn set_memory(&mut self, memory: Rc<Node>) {
+        self.reset();
+        self.cursor.set_root(memory);
+        self.literal = None;
+        self.verbatim = false;
+        self.last_timestamp = None;
+        self.candidate = None;
     }
 
-    /// Preprocess the keyboard input event and returns infos on his internal changes (change on
-    /// the cursor and/or something to commit).
+    /// Sets the delay after which the current sequence is ended.
     ///
-    /// It's useful when you process keyboard input events in bulk. Whether there is something that
-    /// you want to do based on this information, you can decide how to continue.
+    /// The timeout only applies to the keyboard events processed with
+    /// [`Preprocessor::process_with_timestamp`].
     ///
     /// # Example
     ///
     /// ```
-    /// use afrim_preprocessor::{Command, Preprocessor, utils};
-    /// use keyboard_types::{Key::Character, KeyboardEvent};
-    /// use std::{collections::VecDeque, rc::Rc};
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use std::{rc::Rc, time::Duration};
     ///
     /// // We prepare the memory.
-    /// let data = utils::load_data("i3  ī");
+    /// let data = utils::load_data("af  ɑ");
     /// let text_buffer = utils::build_map(data);
     /// let memory = Rc::new(text_buffer);
     ///
     /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_timeout(Some(Duration::from_millis(800)));
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sets the case rules to apply at runtime.
     ///
-    /// // We process the input.
-    /// // let input = "si3";
+    /// It permits to handle the first-letter capitalization and the caps lock, without storing
+    /// the uppercase sequences in the memory. See [`CaseRules`] for more details.
     ///
-    /// let info = preprocessor.process(KeyboardEvent {
-    ///     key: Character("s".to_string()),
-    ///     ..Default::default()
-    /// });
-    /// assert_eq!(info, (true, false));
+    /// # Example
     ///
-    /// let info = preprocessor.process(KeyboardEvent {
-    ///     key: Character("i".to_string()),
-    ///     ..Default::default()
-    /// });
-    /// assert_eq!(info, (true, false));
+    /// ```
+    /// use afrim_preprocessor::{CaseRules, Command, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{collections::VecDeque, rc::Rc};
     ///
-    /// let info = preprocessor.process(KeyboardEvent {
-    ///     key: Character("3".to_string()),
-    ///     ..Default::default()
-    /// });
-    /// assert_eq!(info, (true, true));
+    /// // We prepare the memory.
+    /// let data = utils::load_data("af  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
     ///
-    /// // The input inside the preprocessor.
-    /// assert_eq!(preprocessor.get_input(), "si3".to_owned());
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_case_rules(Some(CaseRules::new()));
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("Af")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
     ///
-    /// // The generated commands.
     /// // The expected results without inhibit feature.
     /// #[cfg(not(feature = "inhibit"))]
     /// let mut expecteds = VecDeque::from(vec![
     ///     Command::Pause,
     ///     Command::Delete,
     ///     Command::Delete,
-    ///     Command::CommitText("ī".to_owned()),
+    ///     Command::CommitText("Ɑ".to_owned()),
     ///     Command::Resume,
     /// ]);
     ///
@@ -222,10 +282,7 @@ impl Preprocessor {
     ///     Command::Resume,
     ///     Command::Pause,
     ///     Command::Delete,
-    ///     Command::Resume,
-    ///     Command::Pause,
-    ///     Command::Delete,
-    ///     Command::CommitText("ī".to_owned()),
+    ///     Command::CommitText("Ɑ".to_owned()),
     ///     Command::Resume,
     /// ]);
     ///
@@ -234,112 +291,163 @@ impl Preprocessor {
     ///     assert_eq!(command, expecteds.pop_front().unwrap());
     /// }
     /// ```
-    pub fn process(&mut self, event: KeyboardEvent) -> (bool, bool) {
-        let (mut changed, mut committed) = (false, false);
-
-        match (event.state, event.key) {
-            (KeyState::Down, Key::Named(NamedKey::Backspace)) => {
-                #[cfg(not(feature = "inhibit"))]
-                {
-                    self.pause();
-                    committed = self.soft_rollback();
-                    self.resume();
-                }
-                #[cfg(feature = "inhibit")]
-                self.cursor.clear();
-                changed = true;
-            }
-            (KeyState::Down, Key::Character(character))
-                if character
-                    .chars()
-                    .next()
-                    .map(|e| e.is_alphanumeric() || e.is_ascii_punctuation())
-                    .unwrap_or(false) =>
-            {
-                #[cfg(feature = "inhibit")]
-                self.pause();
-                #[cfg(feature = "inhibit")]
-                self.queue.push_back(Command::Delete);
-
-                let character = character.chars().next().unwrap();
+    pub fn set_case_rules(&mut self, case_rules: Option<CaseRules>) {
+        self.cursor.set_case_rules(case_rules);
+    }
 
-                if let Some(_in) = self.cursor.hit(character) {
-                    #[cfg(not(feature = "inhibit"))]
-                    self.pause();
-                    let mut prev_cursor = self.cursor.clone();
-                    prev_cursor.undo();
-                    #[cfg(not(feature = "inhibit"))]
-                    self.queue.push_back(Command::Delete);
+    /// Sets whether the keys should be identified by their physical position.
+    ///
+    /// When enabled, the character of a key is the one it generates on the US QWERTY layout,
+    /// whatever the layout of the keyboard. It permits to define the sequences of the memory
+    /// against the physical keys. The keys pressed along with a shortcut modifier (e.g. Control)
+    /// and the keys without a code are kept as they are.
+    ///
+    /// **Note**: The shift state is taken from the modifiers of the keyboard events, and from the
+    /// shift keys processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Code, Command, Key, KeyboardEvent, Preprocessor, utils};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("a1  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_physical_keys(true);
+    ///
+    /// // We type "a1" on an AZERTY keyboard.
+    /// preprocessor.process(KeyboardEvent::key_down(
+    ///     Key::Character("q".to_owned()),
+    ///     Code::KeyA,
+    /// ));
+    /// preprocessor.process(KeyboardEvent::key_down(
+    ///     Key::Character("&".to_owned()),
+    ///     Code::Digit1,
+    /// ));
+    ///
+    /// assert_eq!(preprocessor.get_input(), "a1".to_owned());
+    /// #[cfg(not(feature = "inhibit"))]
+    /// assert!(std::iter::from_fn(|| preprocessor.pop_queue())
+    ///     .any(|command| command == Command::CommitText("ɑ".to_owned())));
+    /// ```
+    pub fn set_physical_keys(&mut self, enabled: bool) {
+        self.physical_keys = enabled;
+    }
 
-                    // Remove the remaining code
-                    while let (None, 1.., ..) = prev_cursor.state() {
-                        prev_cursor.undo();
-                        #[cfg(not(feature = "inhibit"))]
-                        self.queue.push_back(Command::Delete);
-                    }
+    // Replaces the key of a keyboard event by the character of its physical key.
+    fn resolve_physical_key(&self, mut event: KeyboardEvent) -> KeyboardEvent {
+        let shortcut = Modifiers::CONTROL | Modifiers::ALT | Modifiers::ALT_GRAPH | Modifiers::META;
 
-                    if let (Some(out), ..) = prev_cursor.state() {
-                        (0..out.chars().count()).for_each(|_| self.queue.push_back(Command::Delete))
-                    }
+        if !self.physical_keys
+            || event.modifiers.intersects(shortcut)
+            || !matches!(event.key, Key::Character(_) | Key::Named(NamedKey::Dead))
+        {
+            return event;
+        }
 
-                    self.queue.push_back(Command::CommitText(_in));
-                    #[cfg(not(feature = "inhibit"))]
-                    self.resume();
-                    committed = true;
-                };
+        let shifted = self.shift || event.modifiers.contains(Modifiers::SHIFT);
+        let caps_lock = event.modifiers.contains(Modifiers::CAPS_LOCK);
 
-                #[cfg(feature = "inhibit")]
-                self.resume();
-                changed = true;
-            }
-            (KeyState::Down, Key::Named(NamedKey::Shift) | Key::Named(NamedKey::CapsLock)) => (),
-            (KeyState::Down, _) => {
-                self.cursor.clear();
-                changed = true;
-            }
-            _ => (),
-        };
+        if let Some(character) = physical::to_character(event.code, shifted, caps_lock) {
+            event.key = Key::Character(character.to_string());
+        }
 
-        (changed, committed)
+        event
     }
 
-    /// Commit a text.
+    /// Sets whether holding a key offers the alternates reachable from the current sequence.
     ///
-    /// Generate a command to ensure the commitment of this text.
-    /// Useful when you want deal with auto-completion.
+    /// When enabled, the repetitions of a held key are discarded, and the first of them generates
+    /// a [`Command::Alternates`] listing the values reachable from the current sequence. The
+    /// selection of an alternate is done with [`Preprocessor::select_alternate`]. When nothing is
+    /// reachable, the repetitions are processed as usual.
     ///
-    /// **Note**: Before any commitment, the preprocessor make sure to discard the current input.
+    /// **Note**: The repetitions are identified by the `repeat` field of the keyboard events.
     ///
     /// # Example
     ///
     /// ```
     /// use afrim_preprocessor::{Command, Preprocessor, utils};
     /// use keyboard_types::{Key::Character, KeyboardEvent};
-    /// use std::{collections::VecDeque, rc::Rc};
+    /// use std::rc::Rc;
     ///
     /// // We prepare the memory.
-    /// let data = utils::load_data("i3  ī");
+    /// let data = utils::load_data("a1 à\na2 á\naf ɑ");
     /// let text_buffer = utils::build_map(data);
     /// let memory = Rc::new(text_buffer);
     ///
     /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_alternates(true);
     ///
-    /// // We process the input.
-    /// // let input = "si3";
-    /// preprocessor.process(KeyboardEvent {
-    ///     key: Character("s".to_string()),
+    /// // We hold the key "a".
+    /// let event = KeyboardEvent {
+    ///     key: Character("a".to_owned()),
     ///     ..Default::default()
-    /// });
+    /// };
+    /// preprocessor.process(event.clone());
+    /// preprocessor.process(KeyboardEvent { repeat: true, ..event.clone() });
+    /// preprocessor.process(KeyboardEvent { repeat: true, ..event });
     ///
-    /// preprocessor.commit("sī".to_owned());
+    /// assert_eq!(preprocessor.get_input(), "a".to_owned());
+    /// let alternates: Vec<_> = std::iter::from_fn(|| preprocessor.pop_queue())
+    ///     .filter(|command| matches!(command, Command::Alternates(_)))
+    ///     .collect();
+    /// assert_eq!(
+    ///     alternates,
+    ///     vec![Command::Alternates(vec![
+    ///         ("1".to_owned(), "à".to_owned()),
+    ///         ("2".to_owned(), "á".to_owned()),
+    ///         ("f".to_owned(), "ɑ".to_owned()),
+    ///     ])]
+    /// );
+    /// ```
+    pub fn set_alternates(&mut self, enabled: bool) {
+        self.alternates = enabled;
+    }
+
+    /// Sets the key used to escape the next character.
+    ///
+    /// When the escape key is pressed, the next character is sent through untransformed.
+    /// Pressing the escape key twice sends the next characters through untransformed until the
+    /// sequence ends, i.e. until a separator, a non-character key or the timeout. The escape key
+    /// itself is typed by pressing it once more in this mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("af  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_escape_key(Some('\\'));
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("\\af")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
     ///
-    /// // The generated commands.
     /// // The expected results without inhibit feature.
     /// #[cfg(not(feature = "inhibit"))]
     /// let mut expecteds = VecDeque::from(vec![
     ///     Command::Pause,
     ///     Command::Delete,
-    ///     Command::CommitText("sī".to_owned()),
+    ///     Command::Delete,
+    ///     Command::CommitText("a".to_owned()),
     ///     Command::Resume,
     /// ]);
     ///
@@ -350,8 +458,11 @@ impl Preprocessor {
     ///     Command::Delete,
     ///     Command::Resume,
     ///     Command::Pause,
-    ///     Command::CleanDelete,
-    ///     Command::CommitText("sī".to_owned()),
+    ///     Command::Delete,
+    ///     Command::CommitText("a".to_owned()),
+    ///     Command::Resume,
+    ///     Command::Pause,
+    ///     Command::Delete,
     ///     Command::Resume,
     /// ]);
     ///
@@ -360,29 +471,1083 @@ impl Preprocessor {
     ///     assert_eq!(command, expecteds.pop_front().unwrap());
     /// }
     /// ```
-    pub fn commit(&mut self, text: String) {
-        self.pause();
-
-        while !self.cursor.is_empty() {
-            #[cfg(not(feature = "inhibit"))]
-            self.hard_rollback();
-            #[cfg(feature = "inhibit")]
-            self.soft_rollback();
-        }
-        #[cfg(feature = "inhibit")]
-        self.cursor.clear();
-        self.queue.push_back(Command::CommitText(text));
-        self.resume();
-        // We clear the buffer
-        self.cursor.clear();
-    }
-
-    // Pauses the keyboard event listerner.
-    fn pause(&mut self) {
-        self.queue.push_back(Command::Pause);
+    pub fn set_escape_key(&mut self, key: Option<char>) {
+        self.escape_key = key;
     }
 
-    // Resumes the keyboard event listener.
+    /// Sends the next character through untransformed.
+    ///
+    /// Unlike the escape key, this one-shot literal mode don't require a key to be typed.
+    /// Typing the escape key afterwards escapes the whole sequence, as if it was pressed twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("af  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.escape();
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("af")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    ///
+    /// // Nothing has been transformed.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// assert_eq!(preprocessor.pop_queue(), None);
+    /// assert_eq!(preprocessor.get_input(), "af".to_owned());
+    /// ```
+    pub fn escape(&mut self) {
+        self.literal = Some(false);
+    }
+
+    /// Sets the characters that end the current word.
+    ///
+    /// When a separator is pressed, the current input is reset and the word is added to the
+    /// history. If a candidate has been set, it's committed in place of the word.
+    /// See [`Preprocessor::set_candidate`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("af  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_separators(vec![' ', '.']);
+    /// preprocessor.set_history_size(2);
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("maf mba. ")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    ///
+    /// assert_eq!(preprocessor.get_input(), "".to_owned());
+    /// assert_eq!(preprocessor.get_history(), vec!["maf".to_owned(), "mba".to_owned()]);
+    /// ```
+    pub fn set_separators(&mut self, separators: Vec<char>) {
+        self.separators = separators;
+    }
+
+    /// Sets the text to commit when a separator ends the current word.
+    ///
+    /// Useful to commit the top predicate of a translator. The candidate is discarded when the
+    /// current input is reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let text_buffer = utils::build_map(vec![]);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_separators(vec![' ']);
+    ///
+    /// let mut process = |preprocessor: &mut Preprocessor, input| {
+    ///     webdriver::send_keys(input)
+    ///         .into_iter()
+    ///         .for_each(|event| {
+    ///             match event {
+    ///                 Event::Keyboard(event) => preprocessor.process(event),
+    ///                 _ => unimplemented!(),
+    ///             };
+    ///         });
+    /// };
+    ///
+    /// // We process the input.
+    /// process(&mut preprocessor, "hi");
+    /// preprocessor.clear_queue();
+    /// preprocessor.set_candidate(Some("hello".to_owned()));
+    /// process(&mut preprocessor, " ");
+    ///
+    /// // The expected results without inhibit feature.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Delete,
+    ///     Command::Delete,
+    ///     Command::CommitText("hello ".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // The expected results with inhibit feature.
+    /// #[cfg(feature = "inhibit")]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::CleanDelete,
+    ///     Command::CleanDelete,
+    ///     Command::CommitText("hello ".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // Verification.
+    /// while let Some(command) = preprocessor.pop_queue() {
+    ///     assert_eq!(command, expecteds.pop_front().unwrap());
+    /// }
+    /// assert_eq!(preprocessor.get_input(), "".to_owned());
+    /// ```
+    pub fn set_candidate(&mut self, candidate: Option<String>) {
+        self.candidate = candidate;
+    }
+
+    /// Sets the max number of words to keep in the history.
+    ///
+    /// The history is disabled by default.
+    pub fn set_history_size(&mut self, size: usize) {
+        self.history_size = size;
+
+        while self.history.len() > size {
+            self.history.pop_front();
+        }
+    }
+
+    /// Returns the last words, from the oldest to the most recent.
+    ///
+    /// A word is either a committed text or an input ended by a separator.
+    /// See [`Preprocessor::set_separators`] for an example.
+    pub fn get_history(&self) -> Vec<String> {
+        self.history.iter().cloned().collect()
+    }
+
+    // Adds a word to the history.
+    fn remember(&mut self, word: String) {
+        if self.history_size == 0 || word.is_empty() {
+            return;
+        }
+        if self.history.len() == self.history_size {
+            self.history.pop_front();
+        }
+        self.history.push_back(word);
+    }
+
+    // Ends the current word.
+    //
+    // Returns true if something has been committed.
+    fn end_word(&mut self, separator: char) -> bool {
+        let candidate = self.candidate.take().filter(|_| !self.cursor.is_empty());
+        let input = self.get_input();
+        let word = candidate.clone().unwrap_or_else(|| input.clone());
+        let mut committed = false;
+
+        if let Some(text) = candidate {
+            self.pause();
+            // We remove the separator
+            self.queue.push_back(Command::Delete);
+
+            while !self.cursor.is_empty() {
+                #[cfg(not(feature = "inhibit"))]
+                self.hard_rollback();
+                #[cfg(feature = "inhibit")]
+                self.soft_rollback();
+            }
+            let text = format!("{text}{separator}");
+            self.queue.push_back(Command::CommitText(text.clone()));
+            self.resume();
+            self.notify(|| Notification::Commit {
+                input: input.clone(),
+                sequence: input,
+                text,
+            });
+            committed = true;
+        } else if cfg!(feature = "inhibit") {
+            self.pause();
+            self.queue.push_back(Command::Delete);
+            self.queue
+                .push_back(Command::CommitText(separator.to_string()));
+            self.resume();
+            self.notify(|| Notification::Commit {
+                input,
+                text: separator.to_string(),
+                sequence: separator.to_string(),
+            });
+            committed = true;
+        }
+
+        self.remember(word);
+        self.reset();
+
+        committed
+    }
+
+    /// Adds an observer to notify of the activity of the preprocessor.
+    ///
+    /// See [`Observer`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Notification, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{cell::RefCell, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("uu ʉ\nuuaf ʉɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let notifications = Rc::new(RefCell::new(vec![]));
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.add_observer({
+    ///     let notifications = notifications.clone();
+    ///     move |notification: &Notification| {
+    ///         notifications.borrow_mut().push(notification.clone())
+    ///     }
+    /// });
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("muuaf")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    ///
+    /// assert_eq!(
+    ///     *notifications.borrow(),
+    ///     vec![
+    ///         Notification::Commit {
+    ///             input: "muu".to_owned(),
+    ///             text: "ʉ".to_owned(),
+    ///             sequence: "uu".to_owned(),
+    ///         },
+    ///         Notification::Commit {
+    ///             input: "muuaf".to_owned(),
+    ///             text: "ʉɑ".to_owned(),
+    ///             sequence: "uuaf".to_owned(),
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Removes all the observers.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    // Notifies the observers.
+    fn notify(&mut self, notification: impl FnOnce() -> Notification) {
+        if !self.observers.is_empty() {
+            self.observers.notify(&notification());
+        }
+    }
+
+    // Discards the current input.
+    fn reset(&mut self) {
+        if !self.cursor.is_empty() {
+            let input = self.get_input();
+            self.notify(|| Notification::Reset { input });
+        }
+        self.cursor.clear();
+    }
+
+    // Returns the sequence that produced the current state of the cursor.
+    fn last_sequence(&self) -> String {
+        Self::sequence_of(&self.cursor)
+    }
+
+    // Returns the sequence that produced the state of a cursor.
+    fn sequence_of(cursor: &Cursor) -> String {
+        let (_, depth, ..) = cursor.state();
+        let sequence = cursor.to_sequence();
+
+        sequence[sequence.len().saturating_sub(depth)..]
+            .iter()
+            .collect()
+    }
+
+    /// Starts the recording of a [`Journal`].
+    ///
+    /// The keyboard events processed, the texts committed, the reverts and the commands popped
+    /// from the queue are recorded until [`Preprocessor::stop_recording`] is called.
+    /// A recording already started is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Entry, Preprocessor, utils};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let text_buffer = utils::build_map(vec![]);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.start_recording();
+    ///
+    /// preprocessor.commit("hello".to_owned());
+    /// preprocessor.pop_queue();
+    ///
+    /// let journal = preprocessor.stop_recording().unwrap();
+    /// assert_eq!(
+    ///     journal.entries(),
+    ///     &[
+    ///         Entry::Commit("hello".to_owned()),
+    ///         Entry::Command(Command::Pause),
+    ///     ]
+    /// );
+    /// ```
+    pub fn start_recording(&mut self) {
+        self.journal = Some(Journal::new());
+    }
+
+    /// Stops the recording and returns the journal recorded.
+    pub fn stop_recording(&mut self) -> Option<Journal> {
+        self.journal.take()
+    }
+
+    /// Returns the journal being recorded.
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    /// Returns a mutable reference to the journal being recorded.
+    ///
+    /// Useful to save the journal progressively with [`Journal::drain`].
+    pub fn journal_mut(&mut self) -> Option<&mut Journal> {
+        self.journal.as_mut()
+    }
+
+    // Records an entry in the journal.
+    fn record(&mut self, entry: impl FnOnce() -> Entry) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(entry());
+        }
+    }
+
+    // Cancel the previous operation.
+    fn rollback(&mut self) -> bool {
+        if let Some(out) = self.cursor.undo() {
+            #[cfg(feature = "inhibit")]
+            let start = 0;
+            #[cfg(not(feature = "inhibit"))]
+            let start = 1;
+            let end = out.chars().count();
+
+            (start..end).for_each(|_| self.queue.push_back(Command::Delete));
+
+            // Clear the remaining code
+            while let (None, 1.., ..) = self.cursor.state() {
+                self.cursor.undo();
+            }
+
+            if let (Some(_in), ..) = self.cursor.state() {
+                self.queue.push_back(Command::CommitText(_in));
+            }
+
+            true
+        } else {
+            self.cursor.resume();
+
+            false
+        }
+    }
+
+    // Cancel the previous operation.
+    //
+    // Note that it handles the delete by itself.
+    #[cfg(not(feature = "inhibit"))]
+    fn hard_rollback(&mut self) -> bool {
+        self.queue.push_back(Command::Delete);
+        self.rollback()
+    }
+
+    // Cancel the previous opeartion.
+    //
+    // Note that the delete is supposed already executed.
+    fn soft_rollback(&mut self) -> bool {
+        self.queue.push_back(Command::CleanDelete);
+        self.rollback()
+    }
+
+    /// Preprocess the keyboard input event and returns infos on his internal changes (change on
+    /// the cursor and/or something to commit).
+    ///
+    /// It's useful when you process keyboard input events in bulk. Whether there is something that
+    /// you want to do based on this information, you can decide how to continue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::{Key::Character, KeyboardEvent};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("i3  ī");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    ///
+    /// // We process the input.
+    /// // let input = "si3";
+    ///
+    /// let info = preprocessor.process(KeyboardEvent {
+    ///     key: Character("s".to_string()),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(info, (true, false));
+    ///
+    /// let info = preprocessor.process(KeyboardEvent {
+    ///     key: Character("i".to_string()),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(info, (true, false));
+    ///
+    /// let info = preprocessor.process(KeyboardEvent {
+    ///     key: Character("3".to_string()),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(info, (true, true));
+    ///
+    /// // The input inside the preprocessor.
+    /// assert_eq!(preprocessor.get_input(), "si3".to_owned());
+    ///
+    /// // The generated commands.
+    /// // The expected results without inhibit feature.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Delete,
+    ///     Command::CommitText("ī".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // The expected results with inhibit feature.
+    /// #[cfg(feature = "inhibit")]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Resume,
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Resume,
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::CommitText("ī".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // Verification.
+    /// while let Some(command) = preprocessor.pop_queue() {
+    ///     assert_eq!(command, expecteds.pop_front().unwrap());
+    /// }
+    /// ```
+    pub fn process(&mut self, event: KeyboardEvent) -> (bool, bool) {
+        self.record(|| Entry::Event {
+            event: event.clone(),
+            timestamp: None,
+        });
+
+        self.process_event(event)
+    }
+
+    // Processes a keyboard event without recording it.
+    fn process_event(&mut self, event: KeyboardEvent) -> (bool, bool) {
+        let (mut changed, mut committed) = (false, false);
+
+        if event.key == Key::Named(NamedKey::Shift) {
+            self.shift = event.state == KeyState::Down;
+        }
+        let event = self.resolve_physical_key(event);
+
+        if event.state == KeyState::Down && !event.repeat {
+            self.holding = false;
+        }
+        if self.alternates && event.repeat && matches!(event.key, Key::Character(_)) {
+            let alternates = self.get_alternates();
+
+            if !alternates.is_empty() {
+                // The repetitions of the held key are discarded.
+                self.pause();
+                self.queue.push_back(Command::Delete);
+                self.resume();
+
+                if !self.holding {
+                    self.holding = true;
+                    self.queue.push_back(Command::Alternates(alternates));
+                }
+
+                return (changed, committed);
+            }
+        }
+
+        match (event.state, event.key) {
+            (KeyState::Down, Key::Named(NamedKey::Backspace)) if self.literal == Some(true) => {
+                // The escape key has been deleted.
+                self.literal = None;
+                self.pause();
+                self.queue.push_back(Command::CleanDelete);
+                self.resume();
+                changed = true;
+            }
+            (KeyState::Down, Key::Named(NamedKey::Backspace)) => {
+                self.literal = None;
+                #[cfg(not(feature = "inhibit"))]
+                {
+                    let sequence = self.last_sequence();
+                    let text = self.cursor.state().0;
+
+                    self.pause();
+                    committed = self.soft_rollback();
+                    self.resume();
+
+                    if let Some(text) = text {
+                        let input = self.get_input();
+                        self.notify(|| Notification::Rollback {
+                            input,
+                            text,
+                            sequence,
+                        });
+                    }
+                }
+                #[cfg(feature = "inhibit")]
+                self.reset();
+                changed = true;
+            }
+            (KeyState::Down, Key::Character(character))
+                if self.literal.is_none()
+                    && character
+                        .chars()
+                        .next()
+                        .is_some_and(|e| self.separators.contains(&e)) =>
+            {
+                let separator = character.chars().next().unwrap();
+                self.verbatim = false;
+                committed = self.end_word(separator);
+                changed = true;
+            }
+            (KeyState::Down, Key::Character(character))
+                if character
+                    .chars()
+                    .next()
+                    .map(|e| {
+                        e.is_alphanumeric()
+                            || e.is_ascii_punctuation()
+                            || self.escape_key == Some(e)
+                    })
+                    .unwrap_or(false) =>
+            {
+                #[cfg(feature = "inhibit")]
+                self.pause();
+                #[cfg(feature = "inhibit")]
+                self.queue.push_back(Command::Delete);
+
+                let character = character.chars().next().unwrap();
+
+                if self.literal.is_some() && self.escape_key == Some(character) {
+                    // The escape key pressed twice escapes the whole sequence.
+                    #[cfg(not(feature = "inhibit"))]
+                    {
+                        self.pause();
+                        if self.literal == Some(true) {
+                            self.queue.push_back(Command::Delete);
+                        }
+                        self.queue.push_back(Command::Delete);
+                        self.resume();
+                    }
+                    self.literal = None;
+                    self.verbatim = true;
+                } else if self.verbatim {
+                    #[cfg(feature = "inhibit")]
+                    {
+                        self.queue
+                            .push_back(Command::CommitText(character.to_string()));
+                        committed = true;
+                    }
+
+                    self.cursor.hit_literal(character);
+
+                    if committed {
+                        let input = self.get_input();
+                        self.notify(|| Notification::Commit {
+                            input,
+                            text: character.to_string(),
+                            sequence: character.to_string(),
+                        });
+                    }
+                } else if let Some(_escape_key_displayed) = self.literal.take() {
+                    #[cfg(not(feature = "inhibit"))]
+                    if _escape_key_displayed {
+                        self.pause();
+                        self.queue.push_back(Command::Delete);
+                        self.queue.push_back(Command::Delete);
+                        self.queue
+                            .push_back(Command::CommitText(character.to_string()));
+                        self.resume();
+                        committed = true;
+                    }
+                    #[cfg(feature = "inhibit")]
+                    {
+                        self.queue
+                            .push_back(Command::CommitText(character.to_string()));
+                        committed = true;
+                    }
+
+                    self.cursor.hit_literal(character);
+
+                    if committed {
+                        let input = self.get_input();
+                        self.notify(|| Notification::Commit {
+                            input,
+                            text: character.to_string(),
+                            sequence: character.to_string(),
+                        });
+                    }
+                } else if self.escape_key == Some(character) {
+                    self.literal = Some(cfg!(not(feature = "inhibit")));
+                } else if let Some(_in) = self.cursor.hit(character) {
+                    #[cfg(not(feature = "inhibit"))]
+                    self.pause();
+                    let mut prev_cursor = self.cursor.clone();
+                    prev_cursor.undo();
+                    #[cfg(not(feature = "inhibit"))]
+                    self.queue.push_back(Command::Delete);
+
+                    // Remove the remaining code
+                    while let (None, 1.., ..) = prev_cursor.state() {
+                        prev_cursor.undo();
+                        #[cfg(not(feature = "inhibit"))]
+                        self.queue.push_back(Command::Delete);
+                    }
+
+                    if let (Some(out), ..) = prev_cursor.state() {
+                        (0..out.chars().count()).for_each(|_| self.queue.push_back(Command::Delete))
+                    }
+
+                    self.queue.push_back(Command::CommitText(_in.clone()));
+                    #[cfg(not(feature = "inhibit"))]
+                    self.resume();
+                    committed = true;
+
+                    let (input, sequence) = (self.get_input(), self.last_sequence());
+                    self.notify(|| Notification::Commit {
+                        input,
+                        text: _in,
+                        sequence,
+                    });
+                };
+
+                #[cfg(feature = "inhibit")]
+                self.resume();
+                changed = true;
+            }
+            (KeyState::Down, Key::Named(NamedKey::Shift) | Key::Named(NamedKey::CapsLock)) => (),
+            (KeyState::Down, _) => {
+                self.literal = None;
+                self.verbatim = false;
+                self.reset();
+                changed = true;
+            }
+            _ => (),
+        };
+
+        (changed, committed)
+    }
+
+    /// Preprocess a timestamped keyboard input event.
+    ///
+    /// Works like [`Preprocessor::process`], but ends the current sequence when the delay since
+    /// the previous key press exceeds the timeout. The timestamp is the time elapsed since an
+    /// arbitrary origin, which should be the same for all the events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::{Key::Character, KeyboardEvent};
+    /// use std::{rc::Rc, time::Duration};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("af  ɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    /// preprocessor.set_timeout(Some(Duration::from_millis(800)));
+    ///
+    /// // We type "a", wait, then type "f".
+    /// let info = preprocessor.process_with_timestamp(
+    ///     KeyboardEvent {
+    ///         key: Character("a".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     Duration::from_millis(0),
+    /// );
+    /// assert_eq!(info, (true, false));
+    ///
+    /// let info = preprocessor.process_with_timestamp(
+    ///     KeyboardEvent {
+    ///         key: Character("f".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     Duration::from_millis(1000),
+    /// );
+    /// // Nothing has been committed.
+    /// assert_eq!(info, (true, false));
+    /// ```
+    pub fn process_with_timestamp(
+        &mut self,
+        event: KeyboardEvent,
+        timestamp: Duration,
+    ) -> (bool, bool) {
+        if event.state == KeyState::Down {
+            if let (Some(timeout), Some(last_timestamp)) = (self.timeout, self.last_timestamp) {
+                if timestamp.saturating_sub(last_timestamp) > timeout {
+                    self.cursor.end();
+                    self.verbatim = false;
+                }
+            }
+            self.last_timestamp = Some(timestamp);
+        }
+        self.record(|| Entry::Event {
+            event: event.clone(),
+            timestamp: Some(timestamp),
+        });
+
+        self.process_event(event)
+    }
+
+    /// Commit a text.
+    ///
+    /// Generate a command to ensure the commitment of this text.
+    /// Useful when you want deal with auto-completion.
+    ///
+    /// **Note**: Before any commitment, the preprocessor make sure to discard the current input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::{Key::Character, KeyboardEvent};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("i3  ī");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    ///
+    /// // We process the input.
+    /// // let input = "si3";
+    /// preprocessor.process(KeyboardEvent {
+    ///     key: Character("s".to_string()),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// preprocessor.commit("sī".to_owned());
+    ///
+    /// // The generated commands.
+    /// // The expected results without inhibit feature.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::CommitText("sī".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // The expected results with inhibit feature.
+    /// #[cfg(feature = "inhibit")]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Resume,
+    ///     Command::Pause,
+    ///     Command::CleanDelete,
+    ///     Command::CommitText("sī".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // Verification.
+    /// while let Some(command) = preprocessor.pop_queue() {
+    ///     assert_eq!(command, expecteds.pop_front().unwrap());
+    /// }
+    /// ```
+    pub fn commit(&mut self, text: String) {
+        self.record(|| Entry::Commit(text.clone()));
+        self.remember(text.clone());
+        self.candidate = None;
+        let input = self.get_input();
+        self.pause();
+
+        self.verbatim = false;
+        // We remove the escape key
+        if self.literal.take() == Some(true) {
+            self.queue.push_back(Command::Delete);
+        }
+
+        while !self.cursor.is_empty() {
+            #[cfg(not(feature = "inhibit"))]
+            self.hard_rollback();
+            #[cfg(feature = "inhibit")]
+            self.soft_rollback();
+        }
+        #[cfg(feature = "inhibit")]
+        self.cursor.clear();
+        self.queue.push_back(Command::CommitText(text.clone()));
+        self.resume();
+        // We clear the buffer
+        self.cursor.clear();
+        self.notify(|| Notification::Commit {
+            input: input.clone(),
+            text,
+            sequence: input,
+        });
+    }
+
+    /// Reverts the last conversion.
+    ///
+    /// Replaces the text generated by the last conversion of the current input with the raw
+    /// characters that produced it. The characters typed after this conversion are kept. Returns
+    /// false if the current input didn't generate any text.
+    ///
+    /// **Note**: Like after a commitment, the internal memory of the preprocessor is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("c_  ç");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("c_a")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    /// preprocessor.clear_queue();
+    ///
+    /// // We want the raw input instead of "ça".
+    /// assert!(preprocessor.revert());
+    ///
+    /// // The expected results without inhibit feature.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::Delete,
+    ///     Command::CommitText("c_a".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // The expected results with inhibit feature.
+    /// #[cfg(feature = "inhibit")]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::CommitText("c_a".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // Verification.
+    /// while let Some(command) = preprocessor.pop_queue() {
+    ///     assert_eq!(command, expecteds.pop_front().unwrap());
+    /// }
+    ///
+    /// // Nothing to revert anymore.
+    /// assert!(!preprocessor.revert());
+    /// ```
+    pub fn revert(&mut self) -> bool {
+        let mut cursor = self.cursor.clone();
+        let literals = self.cursor.literals();
+        // The characters typed after the conversion, and the ones displayed.
+        let (mut typed, mut displayed) = (String::new(), String::new());
+
+        let out = loop {
+            match cursor.state() {
+                (Some(out), ..) => break out,
+                _ if cursor.is_empty() => return false,
+                (.., '\0') => cursor.resume(),
+                (.., character) => {
+                    // Only the escaped characters are displayed with the inhibit feature.
+                    if cfg!(not(feature = "inhibit"))
+                        || literals.contains(&(cursor.to_sequence().len() - 1))
+                    {
+                        displayed.insert(0, character);
+                    }
+                    typed.insert(0, character);
+                    cursor.undo();
+                }
+            }
+        };
+        self.record(|| Entry::Revert);
+        self.candidate = None;
+        self.verbatim = false;
+        if self.literal.take() == Some(true) {
+            // The escape key is still displayed.
+            typed.extend(self.escape_key);
+            displayed.extend(self.escape_key);
+        }
+        let text = format!("{out}{displayed}");
+        let raw = format!("{}{typed}", Self::sequence_of(&cursor));
+        let input = self.get_input();
+
+        self.pause();
+        (0..text.chars().count()).for_each(|_| self.queue.push_back(Command::Delete));
+        self.queue.push_back(Command::CommitText(raw.clone()));
+        self.resume();
+        // We clear the buffer
+        self.cursor.clear();
+        self.notify(|| Notification::Revert {
+            input,
+            text,
+            sequence: raw,
+        });
+
+        true
+    }
+
+    /// Selects an alternate of the current sequence.
+    ///
+    /// The alternate is identified by the characters remaining to reach it, and replaces the
+    /// text generated by the current sequence. Returns false if these characters don't lead from
+    /// the current sequence to a value. See [`Preprocessor::get_alternates`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Command, Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::{collections::VecDeque, rc::Rc};
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("a1 à\na2 á");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("ba")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    /// preprocessor.clear_queue();
+    ///
+    /// assert!(!preprocessor.select_alternate("3"));
+    /// assert!(preprocessor.select_alternate("2"));
+    /// assert_eq!(preprocessor.get_input(), "ba2".to_owned());
+    ///
+    /// // The expected results without inhibit feature.
+    /// #[cfg(not(feature = "inhibit"))]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::Delete,
+    ///     Command::CommitText("á".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // The expected results with inhibit feature.
+    /// #[cfg(feature = "inhibit")]
+    /// let mut expecteds = VecDeque::from(vec![
+    ///     Command::Pause,
+    ///     Command::CommitText("á".to_owned()),
+    ///     Command::Resume,
+    /// ]);
+    ///
+    /// // Verification.
+    /// while let Some(command) = preprocessor.pop_queue() {
+    ///     assert_eq!(command, expecteds.pop_front().unwrap());
+    /// }
+    /// ```
+    pub fn select_alternate(&mut self, remaining: &str) -> bool {
+        self.record(|| Entry::Alternate(remaining.to_owned()));
+        let Some((_, text)) = self
+            .get_alternates()
+            .into_iter()
+            .find(|(sequence, _)| sequence == remaining)
+        else {
+            return false;
+        };
+        self.candidate = None;
+
+        self.pause();
+        // Remove the remaining code
+        let mut prev_cursor = self.cursor.clone();
+        while let (None, 1.., ..) = prev_cursor.state() {
+            prev_cursor.undo();
+            #[cfg(not(feature = "inhibit"))]
+            self.queue.push_back(Command::Delete);
+        }
+        if let (Some(out), ..) = prev_cursor.state() {
+            (0..out.chars().count()).for_each(|_| self.queue.push_back(Command::Delete))
+        }
+        self.queue.push_back(Command::CommitText(text.clone()));
+        self.resume();
+        remaining.chars().for_each(|c| {
+            self.cursor.hit(c);
+        });
+
+        let (input, sequence) = (self.get_input(), self.last_sequence());
+        self.notify(|| Notification::Commit {
+            input,
+            text,
+            sequence,
+        });
+
+        true
+    }
+
+    // Pauses the keyboard event listerner.
+    fn pause(&mut self) {
+        self.queue.push_back(Command::Pause);
+    }
+
+    // Resumes the keyboard event listener.
     fn resume(&mut self) {
         self.queue.push_back(Command::Resume);
     }
@@ -429,6 +1594,60 @@ impl Preprocessor {
             .collect::<String>()
     }
 
+    /// Returns the next characters that can continue the current sequence, along with the text
+    /// that each of them would generate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("uu ʉ\nu5 û\nuuaf ʉɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut preprocessor = Preprocessor::new(memory, 8);
+    ///
+    /// // We process the input.
+    /// webdriver::send_keys("u")
+    ///     .into_iter()
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             Event::Keyboard(event) => preprocessor.process(event),
+    ///             _ => unimplemented!(),
+    ///         };
+    ///     });
+    ///
+    /// assert_eq!(
+    ///     preprocessor.get_hints(),
+    ///     vec![('5', Some("û".to_owned())), ('u', Some("ʉ".to_owned()))]
+    /// );
+    /// ```
+    pub fn get_hints(&self) -> Vec<(char, Option<String>)> {
+        // The next character will be kept as it is.
+        if self.literal.is_some() || self.verbatim {
+            return vec![];
+        }
+
+        self.cursor.hints()
+    }
+
+    /// Returns the values reachable from the current sequence, along with the characters
+    /// remaining to reach them.
+    ///
+    /// See [`Preprocessor::set_alternates`] for an example.
+    pub fn get_alternates(&self) -> Vec<(String, String)> {
+        // The next character will be kept as it is.
+        if self.literal.is_some() || self.verbatim {
+            return vec![];
+        }
+
+        self.cursor.alternates()
+    }
+
     /// Returns the next command to be executed.
     ///
     /// The next command is dropped from the queue and can't be returned anymore.
@@ -458,7 +1677,10 @@ impl Preprocessor {
     ///     assert_eq!(command, expecteds.pop_front().unwrap());
     /// }
     pub fn pop_queue(&mut self) -> Option<Command> {
-        self.queue.pop_front()
+        let command = self.queue.pop_front()?;
+        self.record(|| Entry::Command(command.clone()));
+
+        Some(command)
     }
 
     /// Clears the queue.
@@ -483,6 +1705,83 @@ impl Preprocessor {
     pub fn clear_queue(&mut self) {
         self.queue.clear();
     }
+
+    /// Returns the state of the current session.
+    ///
+    /// With the `serde` feature, the state can be saved and restored later with
+    /// [`Preprocessor::set_state`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_preprocessor::{Preprocessor, utils};
+    /// use keyboard_types::webdriver::{self, Event};
+    /// use std::rc::Rc;
+    ///
+    /// // We prepare the memory.
+    /// let data = utils::load_data("uu ʉ\nuuaf ʉɑ");
+    /// let text_buffer = utils::build_map(data);
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let process = |preprocessor: &mut Preprocessor, input| {
+    ///     webdriver::send_keys(input)
+    ///         .into_iter()
+    ///         .for_each(|event| {
+    ///             match event {
+    ///                 Event::Keyboard(event) => preprocessor.process(event),
+    ///                 _ => unimplemented!(),
+    ///             };
+    ///         });
+    /// };
+    ///
+    /// // We start a session.
+    /// let mut preprocessor = Preprocessor::new(memory.clone(), 8);
+    /// process(&mut preprocessor, "uua");
+    /// let state = preprocessor.get_state();
+    ///
+    /// // We continue it in another preprocessor.
+    /// let mut restored = Preprocessor::new(memory, 8);
+    /// restored.set_state(state);
+    /// assert_eq!(restored.get_input(), "uua".to_owned());
+    ///
+    /// process(&mut preprocessor, "f");
+    /// process(&mut restored, "f");
+    /// assert_eq!(
+    ///     std::iter::from_fn(|| restored.pop_queue()).collect::<Vec<_>>(),
+    ///     std::iter::from_fn(|| preprocessor.pop_queue()).collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn get_state(&self) -> State {
+        State {
+            sequences: State::split(self.cursor.to_sequence()),
+            literals: self.cursor.literals(),
+            queue: self.queue.iter().cloned().collect(),
+            literal: self.literal,
+            verbatim: self.verbatim,
+            candidate: self.candidate.clone(),
+            history: self.get_history(),
+            last_timestamp: self.last_timestamp,
+            shift: self.shift,
+            holding: self.holding,
+        }
+    }
+
+    /// Restores the state of a session.
+    ///
+    /// The current session is discarded without notifying the observers.
+    /// See [`Preprocessor::get_state`] for an example.
+    pub fn set_state(&mut self, state: State) {
+        self.cursor.restore(&state.join(), &state.literals);
+        self.queue = state.queue.into();
+        self.literal = state.literal;
+        self.verbatim = state.verbatim;
+        self.candidate = state.candidate;
+        self.history = state.history.into();
+        self.set_history_size(self.history_size);
+        self.last_timestamp = state.last_timestamp;
+        self.shift = state.shift;
+        self.holding = state.holding;
+    }
 }
 
 #[cfg(test)]
@@ -498,131 +1797,823 @@ mod tests {
     use std::collections::VecDeque;
 
     #[test]
-    fn test_process() {
+    fn test_process() {
+        use std::rc::Rc;
+
+        let data = utils::load_data("ccced ç\ncc ç");
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
+        webdriver::send_keys("ccced").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        let mut expecteds = VecDeque::from(vec![
+            // c c
+            Command::Pause,
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            Command::Delete,
+            Command::CommitText("ç".to_owned()),
+            Command::Resume,
+            // c e d
+            Command::Pause,
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            Command::Delete,
+            Command::Delete,
+            Command::CommitText("ç".to_owned()),
+            Command::Resume,
+        ]);
+
+        while let Some(command) = preprocessor.pop_queue() {
+            assert_eq!(command, expecteds.pop_front().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_commit() {
+        use afrim_memory::Node;
+        use keyboard_types::KeyboardEvent;
+
+        let mut preprocessor = Preprocessor::new(Node::default().into(), 8);
+        preprocessor.process(KeyboardEvent {
+            key: Character("a".to_owned()),
+            ..Default::default()
+        });
+        preprocessor.commit("word".to_owned());
+
+        let mut expecteds = VecDeque::from(vec![
+            Command::Pause,
+            #[cfg(feature = "inhibit")]
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            #[cfg(feature = "inhibit")]
+            Command::CleanDelete,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Delete,
+            Command::CommitText("word".to_owned()),
+            Command::Resume,
+        ]);
+
+        while let Some(command) = preprocessor.pop_queue() {
+            assert_eq!(command, expecteds.pop_front().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rollback() {
+        use keyboard_types::KeyboardEvent;
+        use std::rc::Rc;
+
+        let data = utils::load_data("ccced ç\ncc ç");
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
+        let backspace_event = KeyboardEvent {
+            key: Named(NamedKey::Backspace),
+            ..Default::default()
+        };
+
+        webdriver::send_keys("ccced").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+
+        preprocessor.clear_queue();
+        assert_eq!(preprocessor.get_input(), "ccced".to_owned());
+        preprocessor.process(backspace_event.clone());
+        #[cfg(not(feature = "inhibit"))]
+        assert_eq!(preprocessor.get_input(), "cc".to_owned());
+        #[cfg(not(feature = "inhibit"))]
+        preprocessor.process(backspace_event);
+        assert_eq!(preprocessor.get_input(), "".to_owned());
+
+        let mut expecteds = VecDeque::from(vec![
+            Command::Pause,
+            #[cfg(not(feature = "inhibit"))]
+            Command::CleanDelete,
+            Command::CommitText("ç".to_owned()),
+            Command::Resume,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Pause,
+            #[cfg(not(feature = "inhibit"))]
+            Command::CleanDelete,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Resume,
+        ]);
+
+        while let Some(command) = preprocessor.pop_queue() {
+            assert_eq!(command, expecteds.pop_front().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_revert() {
+        use std::rc::Rc;
+
+        let data = include_str!("../data/sample.txt");
+        let data = utils::load_data(data);
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
+
+        // Nothing to revert.
+        preprocessor.start_recording();
+        assert!(!preprocessor.revert());
+        assert_eq!(preprocessor.stop_recording().unwrap().entries(), &[]);
+
+        webdriver::send_keys("auuaf3").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        preprocessor.clear_queue();
+
+        assert!(preprocessor.revert());
+        assert_eq!(preprocessor.get_input(), "".to_owned());
+        assert!(!preprocessor.revert());
+
+        let mut expecteds = VecDeque::from(vec![
+            Command::Pause,
+            Command::Delete,
+            Command::Delete,
+            Command::Delete,
+            Command::Delete,
+            Command::CommitText("uuaf3".to_owned()),
+            Command::Resume,
+        ]);
+
+        while let Some(command) = preprocessor.pop_queue() {
+            assert_eq!(command, expecteds.pop_front().unwrap());
+        }
+        assert!(expecteds.is_empty());
+
+        // The last conversion is reverted after the next characters.
+        webdriver::send_keys("c_c").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        preprocessor.clear_queue();
+        assert!(preprocessor.revert());
+        assert!(!preprocessor.revert());
+
+        let mut expecteds = VecDeque::from(vec![
+            Command::Pause,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Delete,
+            Command::Delete,
+            Command::CommitText("c_c".to_owned()),
+            Command::Resume,
+        ]);
+
+        while let Some(command) = preprocessor.pop_queue() {
+            assert_eq!(command, expecteds.pop_front().unwrap());
+        }
+        assert!(expecteds.is_empty());
+
+        // The raw input without conversion is not reverted.
+        webdriver::send_keys("cc").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        assert!(!preprocessor.revert());
+    }
+
+    #[test]
+    fn test_escape() {
+        use keyboard_types::KeyboardEvent;
         use std::rc::Rc;
 
-        let data = utils::load_data("ccced ç\ncc ç");
+        let data = include_str!("../data/sample.txt");
+        let data = utils::load_data(data);
         let memory = utils::build_map(data);
-        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
-        webdriver::send_keys("ccced").into_iter().for_each(|e| {
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 16);
+        let backspace_event = KeyboardEvent {
+            key: Named(NamedKey::Backspace),
+            ..Default::default()
+        };
+        preprocessor.set_escape_key(Some('\\'));
+
+        // The escape key is deleted.
+        webdriver::send_keys("\\").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        preprocessor.process(backspace_event.clone());
+
+        // The escaped character doesn't continue the sequence.
+        webdriver::send_keys("u\\uuu\\u").into_iter().for_each(|e| {
             match e {
                 Event::Keyboard(e) => preprocessor.process(e),
                 _ => unimplemented!(),
             };
         });
+        assert_eq!(preprocessor.get_input(), "uuuuu".to_owned());
+
+        // The escaped character can be deleted.
+        preprocessor.process(backspace_event);
+        #[cfg(not(feature = "inhibit"))]
+        assert_eq!(preprocessor.get_input(), "uuuu".to_owned());
+        #[cfg(feature = "inhibit")]
+        assert_eq!(preprocessor.get_input(), "".to_owned());
+
         let mut expecteds = VecDeque::from(vec![
-            // c c
+            // \ backspace
+            #[cfg(feature = "inhibit")]
             Command::Pause,
+            #[cfg(feature = "inhibit")]
             Command::Delete,
             #[cfg(feature = "inhibit")]
             Command::Resume,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Pause,
+            #[cfg(not(feature = "inhibit"))]
+            Command::CleanDelete,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Resume,
+            // u
             #[cfg(feature = "inhibit")]
             Command::Pause,
+            #[cfg(feature = "inhibit")]
             Command::Delete,
-            Command::CommitText("ç".to_owned()),
+            #[cfg(feature = "inhibit")]
             Command::Resume,
-            // c e d
+            // \ u
+            #[cfg(feature = "inhibit")]
             Command::Pause,
+            #[cfg(feature = "inhibit")]
             Command::Delete,
             #[cfg(feature = "inhibit")]
             Command::Resume,
-            #[cfg(feature = "inhibit")]
             Command::Pause,
             Command::Delete,
+            #[cfg(not(feature = "inhibit"))]
+            Command::Delete,
+            Command::CommitText("u".to_owned()),
+            Command::Resume,
+            // u u
+            Command::Pause,
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
             #[cfg(feature = "inhibit")]
+            Command::Pause,
+            Command::Delete,
+            Command::CommitText("ʉ".to_owned()),
             Command::Resume,
+            // \ u
             #[cfg(feature = "inhibit")]
             Command::Pause,
+            #[cfg(feature = "inhibit")]
             Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            Command::Pause,
             Command::Delete,
-            Command::CommitText("ç".to_owned()),
+            #[cfg(not(feature = "inhibit"))]
+            Command::Delete,
+            Command::CommitText("u".to_owned()),
+            Command::Resume,
+            // backspace
+            #[cfg(not(feature = "inhibit"))]
+            Command::Pause,
+            #[cfg(not(feature = "inhibit"))]
+            Command::CleanDelete,
+            #[cfg(not(feature = "inhibit"))]
             Command::Resume,
         ]);
 
         while let Some(command) = preprocessor.pop_queue() {
             assert_eq!(command, expecteds.pop_front().unwrap());
         }
+        assert!(expecteds.is_empty());
+
+        // The escape key can be any character.
+        preprocessor.set_escape_key(Some('§'));
+        webdriver::send_keys("§uu").into_iter().for_each(|e| {
+            match e {
+                Event::Keyboard(e) => preprocessor.process(e),
+                _ => unimplemented!(),
+            };
+        });
+        assert!(std::iter::from_fn(|| preprocessor.pop_queue())
+            .all(|command| command != Command::CommitText("ʉ".to_owned())));
     }
 
     #[test]
-    fn test_commit() {
-        use afrim_memory::Node;
-        use keyboard_types::KeyboardEvent;
+    fn test_escape_sequence() {
+        use std::rc::Rc;
 
-        let mut preprocessor = Preprocessor::new(Node::default().into(), 8);
-        preprocessor.process(KeyboardEvent {
-            key: Character("a".to_owned()),
-            ..Default::default()
-        });
-        preprocessor.commit("word".to_owned());
+        let data = include_str!("../data/sample.txt");
+        let data = utils::load_data(data);
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 16);
+        preprocessor.set_separators(vec![' ']);
+        preprocessor.set_escape_key(Some('\\'));
+        let send_keys = |preprocessor: &mut Preprocessor, keys: &str| {
+            webdriver::send_keys(keys).into_iter().for_each(|e| {
+                match e {
+                    Event::Keyboard(e) => preprocessor.process(e),
+                    _ => unimplemented!(),
+                };
+            });
+        };
+
+        // The escape key pressed twice escapes the whole sequence.
+        // The escape key escapes himself in this mode.
+        send_keys(&mut preprocessor, "\\\\uu\\u");
+        assert_eq!(preprocessor.get_input(), "uu\\u".to_owned());
+        assert_eq!(preprocessor.get_hints(), vec![]);
+
+        // The separator ends the sequence.
+        send_keys(&mut preprocessor, " uu");
 
         let mut expecteds = VecDeque::from(vec![
+            // \ \
+            #[cfg(feature = "inhibit")]
             Command::Pause,
             #[cfg(feature = "inhibit")]
             Command::Delete,
             #[cfg(feature = "inhibit")]
             Command::Resume,
-            #[cfg(feature = "inhibit")]
             Command::Pause,
-            #[cfg(feature = "inhibit")]
-            Command::CleanDelete,
+            Command::Delete,
             #[cfg(not(feature = "inhibit"))]
             Command::Delete,
-            Command::CommitText("word".to_owned()),
+            Command::Resume,
+        ]);
+        // u u \ u space
+        #[cfg(feature = "inhibit")]
+        expecteds.extend(
+            ["u", "u", "\\", "u", " "]
+                .map(|text| {
+                    [
+                        Command::Pause,
+                        Command::Delete,
+                        Command::CommitText(text.to_owned()),
+                        Command::Resume,
+                    ]
+                })
+                .concat(),
+        );
+        // u u
+        expecteds.extend([
+            Command::Pause,
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            Command::Delete,
+            Command::CommitText("ʉ".to_owned()),
             Command::Resume,
         ]);
 
         while let Some(command) = preprocessor.pop_queue() {
             assert_eq!(command, expecteds.pop_front().unwrap());
         }
+        assert!(expecteds.is_empty());
     }
 
     #[test]
-    fn test_rollback() {
-        use keyboard_types::KeyboardEvent;
-        use std::rc::Rc;
+    fn test_timeout() {
+        use std::{rc::Rc, time::Duration};
 
-        let data = utils::load_data("ccced ç\ncc ç");
+        let data = include_str!("../data/sample.txt");
+        let data = utils::load_data(data);
         let memory = utils::build_map(data);
         let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
-        let backspace_event = KeyboardEvent {
-            key: Named(NamedKey::Backspace),
-            ..Default::default()
+        let mut timestamp = Duration::ZERO;
+        let mut send_keys = |preprocessor: &mut Preprocessor, keys: &str, delay: u64| {
+            webdriver::send_keys(keys).into_iter().for_each(|e| {
+                match e {
+                    Event::Keyboard(e) => {
+                        timestamp += Duration::from_millis(delay);
+                        preprocessor.process_with_timestamp(e, timestamp)
+                    }
+                    _ => unimplemented!(),
+                };
+            });
         };
 
-        webdriver::send_keys("ccced").into_iter().for_each(|e| {
-            match e {
-                Event::Keyboard(e) => preprocessor.process(e),
-                _ => unimplemented!(),
-            };
-        });
-
+        // Without timeout.
+        send_keys(&mut preprocessor, "u", 0);
+        send_keys(&mut preprocessor, "u", 2000);
+        assert_eq!(preprocessor.get_input(), "uu".to_owned());
         preprocessor.clear_queue();
-        assert_eq!(preprocessor.get_input(), "ccced".to_owned());
-        preprocessor.process(backspace_event.clone());
-        #[cfg(not(feature = "inhibit"))]
-        assert_eq!(preprocessor.get_input(), "cc".to_owned());
-        #[cfg(not(feature = "inhibit"))]
-        preprocessor.process(backspace_event);
-        assert_eq!(preprocessor.get_input(), "".to_owned());
+
+        // With timeout.
+        preprocessor.set_timeout(Some(Duration::from_millis(500)));
+        send_keys(&mut preprocessor, "af", 100);
+        send_keys(&mut preprocessor, "f", 1000);
+        send_keys(&mut preprocessor, "3", 100);
 
         let mut expecteds = VecDeque::from(vec![
+            // a f
             Command::Pause,
-            #[cfg(not(feature = "inhibit"))]
-            Command::CleanDelete,
-            Command::CommitText("ç".to_owned()),
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
             Command::Resume,
-            #[cfg(not(feature = "inhibit"))]
+            #[cfg(feature = "inhibit")]
             Command::Pause,
-            #[cfg(not(feature = "inhibit"))]
-            Command::CleanDelete,
-            #[cfg(not(feature = "inhibit"))]
+            Command::Delete,
+            Command::CommitText("ɑ".to_owned()),
+            Command::Resume,
+            // f 3
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            #[cfg(feature = "inhibit")]
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
+            Command::Resume,
+            #[cfg(feature = "inhibit")]
+            Command::Pause,
+            #[cfg(feature = "inhibit")]
+            Command::Delete,
+            #[cfg(feature = "inhibit")]
             Command::Resume,
         ]);
 
         while let Some(command) = preprocessor.pop_queue() {
             assert_eq!(command, expecteds.pop_front().unwrap());
         }
+        assert!(expecteds.is_empty());
+    }
+
+    #[test]
+    fn test_separators() {
+        use std::rc::Rc;
+
+        let data = include_str!("../data/sample.txt");
+        let data = utils::load_data(data);
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 16);
+        preprocessor.set_separators(vec![' ', ',']);
+        preprocessor.set_history_size(2);
+        preprocessor.set_escape_key(Some('\\'));
+        let send_keys = |preprocessor: &mut Preprocessor, keys: &str| {
+            webdriver::send_keys(keys).into_iter().for_each(|e| {
+                match e {
+                    Event::Keyboard(e) => preprocessor.process(e),
+                    _ => unimplemented!(),
+                };
+            });
+        };
+
+        // The candidate is committed in place of the word.
+        send_keys(&mut preprocessor, "2ua");
+        preprocessor.clear_queue();
+        preprocessor.set_candidate(Some("úá!".to_owned()));
+        send_keys(&mut preprocessor, ",");
+        let commands: Vec<_> = std::iter::from_fn(|| preprocessor.pop_queue()).collect();
+        assert_eq!(commands[..2], [Command::Pause, Command::Delete]);
+        assert_eq!(
+            commands[commands.len() - 2..],
+            [Command::CommitText("úá!,".to_owned()), Command::Resume]
+        );
+        assert_eq!(preprocessor.get_input(), "".to_owned());
+
+        // The candidate is discarded with the input.
+        preprocessor.set_candidate(Some("hello".to_owned()));
+        send_keys(&mut preprocessor, " ");
+        #[cfg(not(feature = "inhibit"))]
+        assert_eq!(preprocessor.pop_queue(), None);
+        #[cfg(feature = "inhibit")]
+        assert_eq!(
+            std::iter::from_fn(|| preprocessor.pop_queue()).collect::<Vec<_>>(),
+            vec![
+                Command::Pause,
+                Command::Delete,
+                Command::CommitText(" ".to_owned()),
+                Command::Resume
+            ]
+        );
+
+        // An escaped separator doesn't end the word.
+        send_keys(&mut preprocessor, "a\\,b");
+        assert_eq!(preprocessor.get_input(), "a,b".to_owned());
+
+        // The history is bounded.
+        send_keys(&mut preprocessor, " c ");
+        assert_eq!(
+            preprocessor.get_history(),
+            vec!["a,b".to_owned(), "c".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_observers() {
+        use crate::Notification;
+        use keyboard_types::KeyboardEvent;
+        use std::{cell::RefCell, rc::Rc};
+
+        let data = utils::load_data("c_ ç\nc_c ĉ");
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
+        let notifications = Rc::new(RefCell::new(vec![]));
+        preprocessor.add_observer({
+            let notifications = notifications.clone();
+            move |notification: &Notification| notifications.borrow_mut().push(notification.clone())
+        });
+        let send_keys = |preprocessor: &mut Preprocessor, keys: &str| {
+            webdriver::send_keys(keys).into_iter().for_each(|e| {
+                match e {
+                    Event::Keyboard(e) => preprocessor.process(e),
+                    _ => unimplemented!(),
+                };
+            });
+        };
+        let backspace_event = KeyboardEvent {
+            key: Named(NamedKey::Backspace),
+            ..Default::default()
+        };
+
+        // Commit.
+        send_keys(&mut preprocessor, "ac_");
+        assert_eq!(
+            notifications.borrow_mut().pop(),
+            Some(Notification::Commit {
+                input: "ac_".to_owned(),
+                text: "ç".to_owned(),
+                sequence: "c_".to_owned(),
+            })
+        );
+
+        // Rollback.
+        preprocessor.process(backspace_event);
+        #[cfg(not(feature = "inhibit"))]
+        assert_eq!(
+            notifications.borrow_mut().pop(),
+            Some(Notification::Rollback {
+                input: "a".to_owned(),
+                text: "ç".to_owned(),
+                sequence: "c_".to_owned(),
+            })
+        );
+        #[cfg(feature = "inhibit")]
+        assert_eq!(
+            notifications.borrow_mut().pop(),
+            Some(Notification::Reset {
+                input: "ac_".to_owned(),
+            })
+        );
+
+        // Revert.
+        preprocessor.commit("b".to_owned());
+        notifications.borrow_mut().clear();
+        send_keys(&mut preprocessor, "c_c");
+        notifications.borrow_mut().clear();
+        assert!(preprocessor.revert());
+        assert_eq!(
+            *notifications.borrow(),
+            vec![Notification::Revert {
+                input: "c_c".to_owned(),
+                text: "ĉ".to_owned(),
+                sequence: "c_c".to_owned(),
+            }]
+        );
+
+        // Reset.
+        notifications.borrow_mut().clear();
+        send_keys(&mut preprocessor, "ab");
+        preprocessor.process(KeyboardEvent {
+            key: Named(NamedKey::Escape),
+            ..Default::default()
+        });
+        preprocessor.process(KeyboardEvent {
+            key: Named(NamedKey::Escape),
+            ..Default::default()
+        });
+        assert_eq!(
+            *notifications.borrow(),
+            vec![Notification::Reset {
+                input: "ab".to_owned(),
+            }]
+        );
+
+        // No more notifications.
+        preprocessor.clear_observers();
+        send_keys(&mut preprocessor, "c_");
+        assert_eq!(notifications.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_physical_keys() {
+        use crate::{Code, Modifiers};
+        use keyboard_types::{KeyState, KeyboardEvent};
+        use std::rc::Rc;
+
+        let data = utils::load_data("a1 ɑ\nA! Ɑ");
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 8);
+        let key =
+            |character: &str, code| KeyboardEvent::key_down(Character(character.to_owned()), code);
+
+        // Character-based by default.
+        preprocessor.process(key("q", Code::KeyA));
+        assert_eq!(preprocessor.get_input(), "q".to_owned());
+
+        // Physical-based.
+        preprocessor.set_physical_keys(true);
+        preprocessor.commit("".to_owned());
+        preprocessor.process(key("q", Code::KeyA));
+        preprocessor.process(key("&", Code::Digit1));
+        assert_eq!(preprocessor.get_input(), "a1".to_owned());
+
+        // The shift keys are tracked.
+        preprocessor.commit("".to_owned());
+        preprocessor.process(KeyboardEvent::key_down(
+            Named(NamedKey::Shift),
+            Code::ShiftLeft,
+        ));
+        preprocessor.process(key("Q", Code::KeyA));
+        preprocessor.process(key("1", Code::Digit1));
+        preprocessor.process(KeyboardEvent::key_up(
+            Named(NamedKey::Shift),
+            Code::ShiftLeft,
+        ));
+        preprocessor.process(key("&", Code::Digit1));
+        assert_eq!(preprocessor.get_input(), "A!1".to_owned());
+
+        // The shortcuts and the keys without code are kept as they are.
+        preprocessor.commit("".to_owned());
+        preprocessor.process(KeyboardEvent {
+            modifiers: Modifiers::CONTROL,
+            ..key("q", Code::KeyA)
+        });
+        preprocessor.process(KeyboardEvent {
+            state: KeyState::Down,
+            key: Character("é".to_owned()),
+            ..Default::default()
+        });
+        assert_eq!(preprocessor.get_input(), "qé".to_owned());
+        preprocessor.clear_queue();
+    }
+
+    #[test]
+    fn test_alternates() {
+        use crate::Command;
+        use keyboard_types::KeyboardEvent;
+        use std::rc::Rc;
+
+        let data = utils::load_data("a1 à\na2 á\nb1 ɓ");
+        let memory = utils::build_map(data);
+        let mut preprocessor = Preprocessor::new(Rc::new(memory), 16);
+        let key = |character: &str, repeat| KeyboardEvent {
+            key: Character(character.to_owned()),
+            repeat,
+            ..Default::default()
+        };
+        let alternates = |preprocessor: &mut Preprocessor| {
+            std::iter::from_fn(|| preprocessor.pop_queue())
+                .filter(|command| matches!(command, Command::Alternates(_)))
+                .count()
+        };
+
+        // The repetitions are processed as usual by default.
+        preprocessor.process(key("a", false));
+        preprocessor.process(key("a", true));
+        assert_eq!(preprocessor.get_input(), "aa".to_owned());
+        assert_eq!(alternates(&mut preprocessor), 0);
+
+        // The alternates are offered once per hold.
+        preprocessor.set_alternates(true);
+        preprocessor.start_recording();
+        preprocessor.process(key("b", false));
+        preprocessor.process(key("b", true));
+        preprocessor.process(key("b", true));
+        assert_eq!(preprocessor.get_input(), "aab".to_owned());
+        assert_eq!(
+            preprocessor.get_alternates(),
+            vec![("1".to_owned(), "ɓ".to_owned())]
+        );
+        assert_eq!(alternates(&mut preprocessor), 1);
+        preprocessor.process(key("a", false));
+        preprocessor.process(key("a", true));
+        assert_eq!(alternates(&mut preprocessor), 1);
+
+        // Selection of an alternate.
+        assert!(!preprocessor.select_alternate("b1"));
+        assert_eq!(preprocessor.get_input(), "aaba".to_owned());
+        assert!(preprocessor.select_alternate("2"));
+        assert_eq!(preprocessor.get_input(), "aaba2".to_owned());
+        assert!(!preprocessor.select_alternate(""));
+        while preprocessor.pop_queue().is_some() {}
+        let journal = preprocessor.stop_recording().unwrap();
+        let memory = utils::build_map(utils::load_data("a1 à\na2 á\nb1 ɓ"));
+        let mut replayer = Preprocessor::new(Rc::new(memory), 16);
+        replayer.set_alternates(true);
+        assert_eq!(journal.replay(&mut replayer), Ok(()));
+
+        // The repetitions without alternates are processed as usual.
+        preprocessor.process(key("z", false));
+        preprocessor.process(key("z", true));
+        assert_eq!(preprocessor.get_input(), "aaba2zz".to_owned());
+        assert_eq!(alternates(&mut preprocessor), 0);
+    }
+
+    #[test]
+    fn test_state() {
+        use keyboard_types::{webdriver, KeyboardEvent};
+        use std::rc::Rc;
+
+        let data = utils::load_data("c_ ç\nc_c ĉ\naf ɑ");
+        let memory = Rc::new(utils::build_map(data));
+        let process = |preprocessor: &mut Preprocessor, input| {
+            webdriver::send_keys(input)
+                .into_iter()
+                .for_each(|event| match event {
+                    webdriver::Event::Keyboard(event) => {
+                        preprocessor.process(event);
+                    }
+                    _ => unimplemented!(),
+                });
+        };
+        let build = || {
+            let mut preprocessor = Preprocessor::new(memory.clone(), 32);
+            preprocessor.set_escape_key(Some('\\'));
+            preprocessor.set_separators(vec![' ']);
+            preprocessor.set_history_size(4);
+            preprocessor
+        };
+
+        let mut preprocessor = build();
+        process(&mut preprocessor, "a c_ \\a");
+        preprocessor.set_candidate(Some("ok".to_owned()));
+        process(&mut preprocessor, "\\");
+
+        // The pending queue and the mode are restored.
+        let state = preprocessor.get_state();
+        let mut restored = build();
+        restored.set_state(state.clone());
+        assert_eq!(restored.get_state(), state);
+        assert_eq!(restored.get_input(), preprocessor.get_input());
+        assert_eq!(
+            restored.get_history(),
+            vec!["a".to_owned(), "c_".to_owned()]
+        );
+
+        // The subsequent commands are identical.
+        [&mut preprocessor, &mut restored]
+            .into_iter()
+            .for_each(|preprocessor| {
+                process(preprocessor, "af");
+                preprocessor.process(KeyboardEvent {
+                    key: Named(NamedKey::Backspace),
+                    ..Default::default()
+                });
+                process(preprocessor, "c_c ");
+            });
+        assert_eq!(
+            std::iter::from_fn(|| restored.pop_queue()).collect::<Vec<_>>(),
+            std::iter::from_fn(|| preprocessor.pop_queue()).collect::<Vec<_>>()
+        );
+        assert_eq!(restored.get_history(), preprocessor.get_history());
+
+        // The current session is discarded.
+        restored.set_state(Default::default());
+        assert_eq!(restored.get_input(), "".to_owned());
+        assert_eq!(restored.pop_queue(), None);
+
+        // The escaped characters are restored as they are.
+        let mut preprocessor = build();
+        process(&mut preprocessor, "\\a");
+        preprocessor.clear_queue();
+        let mut restored = build();
+        restored.set_state(preprocessor.get_state());
+        assert_eq!(restored.get_state(), preprocessor.get_state());
+
+        [&mut preprocessor, &mut restored]
+            .into_iter()
+            .for_each(|preprocessor| process(preprocessor, "f"));
+        assert_eq!(
+            std::iter::from_fn(|| restored.pop_queue()).collect::<Vec<_>>(),
+            std::iter::from_fn(|| preprocessor.pop_queue()).collect::<Vec<_>>()
+        );
+        assert_eq!(restored.get_input(), "af".to_owned());
     }
 
     #[test]
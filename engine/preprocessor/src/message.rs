@@ -1,6 +1,9 @@
 #![deny(missing_docs)]
 
 /// Possible commands that can be generated by the `afrim-preprocessor`.
+///
+/// New commands can be added in a minor version, the unknown ones should be ignored.
+#[non_exhaustive]
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Command {
@@ -17,4 +20,11 @@ pub enum Command {
     /// Useful by example in case that a key has been pressed, and we want to ensure
     /// that it has been released before continuing.
     CleanDelete,
+    /// Request to offer the alternates of a held key.
+    ///
+    /// Each alternate is made of the characters remaining to reach it and its text.
+    /// See [`Preprocessor::set_alternates`] for more details.
+    ///
+    /// [`Preprocessor::set_alternates`]: crate::Preprocessor::set_alternates
+    Alternates(Vec<(String, String)>),
 }
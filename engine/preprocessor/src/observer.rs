@@ -0,0 +1,139 @@
+#![deny(missing_docs)]
+//! Observe the activity of the preprocessor.
+//!
+//! An observer is notified of the commitments, rollbacks, reverts and resets performed by the
+//! preprocessor. It permits to react to them (e.g. for statistics, learning or display), without
+//! having to deduce them from the commands generated.
+
+use std::fmt;
+
+/// A notification sent to the observers.
+///
+/// The input is the content of the internal memory of the preprocessor and the sequence is the
+/// part of the input concerned by the notification.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Notification {
+    /// A text committed.
+    Commit {
+        /// The input when the text has been committed.
+        input: String,
+        /// The text committed.
+        text: String,
+        /// The sequence that produced the text.
+        sequence: String,
+    },
+    /// A text removed by a backspace.
+    Rollback {
+        /// The input after the rollback.
+        input: String,
+        /// The text removed.
+        text: String,
+        /// The sequence that produced the text.
+        sequence: String,
+    },
+    /// A text replaced by the sequence that produced it.
+    Revert {
+        /// The input before the revert.
+        input: String,
+        /// The text replaced.
+        text: String,
+        /// The sequence committed in place of the text.
+        sequence: String,
+    },
+    /// The input discarded.
+    Reset {
+        /// The input before the reset.
+        input: String,
+    },
+}
+
+/// An observer of the preprocessor.
+///
+/// It's implemented for the closures taking a [`Notification`].
+///
+/// # Example
+///
+/// ```
+/// use afrim_preprocessor::{utils, Notification, Observer, Preprocessor};
+/// use keyboard_types::webdriver::{self, Event};
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// // We count the texts committed.
+/// #[derive(Default)]
+/// struct Counter(Rc<RefCell<usize>>);
+///
+/// impl Observer for Counter {
+///     fn notify(&mut self, notification: &Notification) {
+///         if let Notification::Commit { .. } = notification {
+///             *self.0.borrow_mut() += 1;
+///         }
+///     }
+/// }
+///
+/// // We prepare the memory.
+/// let data = utils::load_data("cc ç");
+/// let text_buffer = utils::build_map(data);
+/// let memory = Rc::new(text_buffer);
+///
+/// let counter = Counter::default();
+/// let count = counter.0.clone();
+///
+/// let mut preprocessor = Preprocessor::new(memory, 8);
+/// preprocessor.add_observer(counter);
+///
+/// // We process the input.
+/// webdriver::send_keys("cccc")
+///     .into_iter()
+///     .for_each(|event| {
+///         match event {
+///             Event::Keyboard(event) => preprocessor.process(event),
+///             _ => unimplemented!(),
+///         };
+///     });
+///
+/// assert_eq!(*count.borrow(), 2);
+/// ```
+pub trait Observer {
+    /// Receives a notification of the preprocessor.
+    fn notify(&mut self, notification: &Notification);
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(&Notification),
+{
+    fn notify(&mut self, notification: &Notification) {
+        self(notification)
+    }
+}
+
+// The observers of a preprocessor.
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Box<dyn Observer>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Box<dyn Observer>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn notify(&mut self, notification: &Notification) {
+        self.0
+            .iter_mut()
+            .for_each(|observer| observer.notify(notification));
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
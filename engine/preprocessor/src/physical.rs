@@ -0,0 +1,93 @@
+#![deny(missing_docs)]
+//! Resolution of the physical keys.
+//!
+//! The layouts based on the physical keys are defined with the characters of the US QWERTY
+//! layout. It permits to type them in the same way, whatever the layout of the keyboard.
+
+use crate::Code;
+
+/// Returns the character generated by a physical key on the US QWERTY layout.
+///
+/// Like on a keyboard, the caps lock only affects the letters.
+pub(crate) fn to_character(code: Code, shifted: bool, caps_lock: bool) -> Option<char> {
+    let (character, shifted_character) = match code {
+        Code::Backquote => ('`', '~'),
+        Code::Digit1 => ('1', '!'),
+        Code::Digit2 => ('2', '@'),
+        Code::Digit3 => ('3', '#'),
+        Code::Digit4 => ('4', '$'),
+        Code::Digit5 => ('5', '%'),
+        Code::Digit6 => ('6', '^'),
+        Code::Digit7 => ('7', '&'),
+        Code::Digit8 => ('8', '*'),
+        Code::Digit9 => ('9', '('),
+        Code::Digit0 => ('0', ')'),
+        Code::Minus => ('-', '_'),
+        Code::Equal => ('=', '+'),
+        Code::KeyQ => ('q', 'Q'),
+        Code::KeyW => ('w', 'W'),
+        Code::KeyE => ('e', 'E'),
+        Code::KeyR => ('r', 'R'),
+        Code::KeyT => ('t', 'T'),
+        Code::KeyY => ('y', 'Y'),
+        Code::KeyU => ('u', 'U'),
+        Code::KeyI => ('i', 'I'),
+        Code::KeyO => ('o', 'O'),
+        Code::KeyP => ('p', 'P'),
+        Code::BracketLeft => ('[', '{'),
+        Code::BracketRight => (']', '}'),
+        Code::Backslash | Code::IntlBackslash => ('\\', '|'),
+        Code::KeyA => ('a', 'A'),
+        Code::KeyS => ('s', 'S'),
+        Code::KeyD => ('d', 'D'),
+        Code::KeyF => ('f', 'F'),
+        Code::KeyG => ('g', 'G'),
+        Code::KeyH => ('h', 'H'),
+        Code::KeyJ => ('j', 'J'),
+        Code::KeyK => ('k', 'K'),
+        Code::KeyL => ('l', 'L'),
+        Code::Semicolon => (';', ':'),
+        Code::Quote => ('\'', '"'),
+        Code::KeyZ => ('z', 'Z'),
+        Code::KeyX => ('x', 'X'),
+        Code::KeyC => ('c', 'C'),
+        Code::KeyV => ('v', 'V'),
+        Code::KeyB => ('b', 'B'),
+        Code::KeyN => ('n', 'N'),
+        Code::KeyM => ('m', 'M'),
+        Code::Comma => (',', '<'),
+        Code::Period => ('.', '>'),
+        Code::Slash => ('/', '?'),
+        Code::Space => (' ', ' '),
+        _ => return None,
+    };
+
+    let shifted = shifted ^ (caps_lock && character.is_ascii_alphabetic());
+
+    Some(if shifted {
+        shifted_character
+    } else {
+        character
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_to_character() {
+        use super::to_character;
+        use crate::Code;
+
+        assert_eq!(to_character(Code::Digit1, false, false), Some('1'));
+        assert_eq!(to_character(Code::Digit1, true, false), Some('!'));
+        assert_eq!(to_character(Code::KeyQ, true, false), Some('Q'));
+        assert_eq!(to_character(Code::Space, true, false), Some(' '));
+        assert_eq!(to_character(Code::Enter, false, false), None);
+        assert_eq!(to_character(Code::Unidentified, false, false), None);
+
+        // The caps lock only affects the letters.
+        assert_eq!(to_character(Code::KeyQ, false, true), Some('Q'));
+        assert_eq!(to_character(Code::KeyQ, true, true), Some('q'));
+        assert_eq!(to_character(Code::Digit1, false, true), Some('1'));
+    }
+}
@@ -0,0 +1,81 @@
+#![deny(missing_docs)]
+//! Save and restore the session of the preprocessor.
+//!
+//! The state of a session is made of the pending input, the commands not yet popped from the
+//! queue and the current mode (e.g. escaping). Restoring it in a preprocessor with the same
+//! configuration permits to continue the session where it stopped.
+
+use crate::Command;
+use std::time::Duration;
+
+/// The logical state of a preprocessor session.
+///
+/// See [`Preprocessor::get_state`] for an example.
+///
+/// [`Preprocessor::get_state`]: crate::Preprocessor::get_state
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct State {
+    /// The sequences in the memory of the cursor, from the oldest to the most recent.
+    pub sequences: Vec<String>,
+    /// The positions, in the sequences joined by `\0`, of the characters kept as they are.
+    pub literals: Vec<usize>,
+    /// The commands not yet popped from the queue.
+    pub queue: Vec<Command>,
+    /// Whether the next character should be kept as it is, and if the escape key is still
+    /// displayed.
+    pub literal: Option<bool>,
+    /// Whether the characters should be kept as they are until the sequence ends.
+    pub verbatim: bool,
+    /// The text to commit when a separator ends the current word.
+    pub candidate: Option<String>,
+    /// The last words committed.
+    pub history: Vec<String>,
+    /// The timestamp of the last key press.
+    pub last_timestamp: Option<Duration>,
+    /// Whether a shift key is pressed.
+    pub shift: bool,
+    /// Whether the alternates of the held key have been offered.
+    pub holding: bool,
+}
+
+impl State {
+    // Splits the sequence of a cursor.
+    pub(crate) fn split(sequence: Vec<char>) -> Vec<String> {
+        sequence
+            .split(|character| *character == '\0')
+            .map(|sequence| sequence.iter().collect())
+            .collect()
+    }
+
+    // Joins the sequences into the sequence of a cursor.
+    pub(crate) fn join(&self) -> Vec<char> {
+        self.sequences.join("\0").chars().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_sequences() {
+        use super::State;
+
+        let sequence = vec!['\0', 'c', '\0', 'c', '_', '\0'];
+        let state = State {
+            sequences: State::split(sequence.clone()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            state.sequences,
+            vec![
+                "".to_owned(),
+                "c".to_owned(),
+                "c_".to_owned(),
+                "".to_owned()
+            ]
+        );
+        assert_eq!(state.join(), sequence);
+        assert_eq!(State::default().join(), vec![]);
+    }
+}
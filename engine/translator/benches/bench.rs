@@ -77,7 +77,45 @@ pub fn translate(c: &mut Criterion) {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-criterion_group!(benches, translate);
+pub fn translate_top_k(c: &mut Criterion) {
+    // Generates a dataset with a lot of completions.
+    let mut dictionary = IndexMap::new();
+    (0..100_000).for_each(|i| {
+        let texts = (0..5).map(|j| format!("text{i}_{j}")).collect();
+        dictionary.insert(format!("word{i}"), texts);
+    });
+
+    // Initializes the translator.
+    let translator = Translator::new(dictionary, false);
+
+    // Setup the benchmark.
+    let mut group = c.benchmark_group("translate_top_k");
+    for input in ["wo", "word1", "word12"] {
+        group.bench_with_input(BenchmarkId::new("all", input), input, |b, input| {
+            b.iter(|| {
+                translator
+                    .translate(black_box(input))
+                    .into_iter()
+                    .take(20)
+                    .count();
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("top_k", input), input, |b, input| {
+            b.iter(|| {
+                translator.translate_top_k(black_box(input), 20);
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("pages", input), input, |b, input| {
+            b.iter(|| {
+                translator.pages(black_box(input), 10).nth(1);
+            });
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion_group!(benches, translate, translate_top_k);
 
 #[cfg(not(target_arch = "wasm32"))]
 criterion_main!(benches);
@@ -0,0 +1,115 @@
+#![deny(missing_docs)]
+//! Predicates not yet selected.
+//!
+//! A candidate borrows the texts of the dictionary, they are cloned only if it is selected.
+
+use crate::{Predicate, Ranking};
+use std::{borrow::Cow, cmp::Ordering};
+
+// A predicate whose texts are not yet cloned.
+//
+// The candidates are ordered from the worst to the best.
+#[derive(Debug)]
+pub(crate) struct Candidate<'a> {
+    // The predicate without its texts.
+    pub(crate) predicate: Predicate,
+    pub(crate) texts: Cow<'a, [String]>,
+    // The priority of its dictionary.
+    pub(crate) priority: i32,
+    // The position among the candidates, the first ones are preferred at equal confidence.
+    pub(crate) order: usize,
+    // The boost given by the selections of the user, added to the confidence to sort.
+    pub(crate) boost: f64,
+}
+
+impl<'a> Candidate<'a> {
+    // Initializes a candidate.
+    pub(crate) fn new(predicate: Predicate, texts: Cow<'a, [String]>, priority: i32) -> Self {
+        Self {
+            predicate,
+            texts,
+            priority,
+            order: 0,
+            boost: 0.0,
+        }
+    }
+
+    // Returns the score used to sort the candidates.
+    fn score(&self) -> f64 {
+        self.predicate.confidence + self.boost
+    }
+
+    // Builds the predicate, with its texts sorted according to the ranking.
+    pub(crate) fn into_predicate(self, ranking: &Ranking) -> Predicate {
+        let mut predicate = self.predicate;
+        predicate.texts = self.texts.into_owned();
+        ranking.sort(&mut predicate);
+
+        predicate
+    }
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score()
+            .total_cmp(&other.score())
+            .then_with(|| self.priority.cmp(&other.priority))
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_candidate() {
+        use super::Candidate;
+        use crate::{Predicate, Ranking};
+        use std::borrow::Cow;
+
+        let texts = vec!["hello".to_owned()];
+        let candidate = |confidence, priority, order| Candidate {
+            order,
+            ..Candidate::new(
+                Predicate {
+                    code: "hi".to_owned(),
+                    confidence,
+                    ..Default::default()
+                },
+                Cow::Borrowed(&texts),
+                priority,
+            )
+        };
+
+        assert!(candidate(1.0, 0, 1) > candidate(0.5, 1, 0));
+        assert!(candidate(0.5, 1, 1) > candidate(0.5, 0, 0));
+        assert!(candidate(0.5, 0, 0) > candidate(0.5, 0, 1));
+        assert_eq!(candidate(0.5, 0, 1), candidate(0.5, 0, 1));
+        // The boost of the ranking is added to the confidence.
+        let boosted = Candidate {
+            boost: 0.6,
+            ..candidate(0.5, 0, 1)
+        };
+        assert!(boosted > candidate(1.0, 0, 0));
+        assert_eq!(boosted.into_predicate(&Ranking::default()).confidence, 0.5);
+        assert_eq!(
+            candidate(0.5, 0, 0)
+                .into_predicate(&Ranking::default())
+                .texts,
+            texts
+        );
+    }
+}
@@ -0,0 +1,86 @@
+#![deny(missing_docs)]
+//! Named sources of translations.
+//!
+//! Each dictionary has its own index, a priority and can be disabled.
+
+use crate::index::Index;
+use indexmap::IndexMap;
+
+// The translations of a named source.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Dictionary {
+    pub(crate) entries: IndexMap<String, Vec<String>>,
+    pub(crate) index: Index,
+    // The higher the better.
+    pub(crate) priority: i32,
+    pub(crate) enabled: bool,
+}
+
+impl Dictionary {
+    // Initializes an enabled dictionary.
+    pub(crate) fn new(entries: IndexMap<String, Vec<String>>, priority: i32) -> Self {
+        Self {
+            index: Index::new(&entries),
+            entries,
+            priority,
+            enabled: true,
+        }
+    }
+
+    // Adds the texts of a code, and returns its previous texts.
+    pub(crate) fn insert(&mut self, code: String, texts: Vec<String>) -> Option<Vec<String>> {
+        let (position, previous) = self.entries.insert_full(code, texts);
+
+        if previous.is_none() {
+            self.index.insert(&self.entries, position);
+        }
+
+        previous
+    }
+
+    // Removes a code, and returns its texts.
+    pub(crate) fn remove(&mut self, code: &str) -> Option<Vec<String>> {
+        let (position, _, texts) = self.entries.shift_remove_full(code)?;
+        self.index.remove(position);
+
+        Some(texts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_dictionary() {
+        use super::Dictionary;
+        use indexmap::IndexMap;
+
+        let mut dictionary = Dictionary::new(IndexMap::new(), 1);
+        assert!(dictionary.enabled);
+
+        assert_eq!(
+            dictionary.insert("hi".to_owned(), vec!["hello".to_owned()]),
+            None
+        );
+        assert_eq!(
+            dictionary.insert("hey".to_owned(), vec!["hello".to_owned()]),
+            None
+        );
+        assert_eq!(
+            dictionary
+                .index
+                .prefixed(&dictionary.entries, "h")
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+
+        assert_eq!(dictionary.remove("hey"), Some(vec!["hello".to_owned()]));
+        assert_eq!(dictionary.remove("hey"), None);
+        assert_eq!(
+            dictionary
+                .index
+                .prefixed(&dictionary.entries, "h")
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+#![deny(missing_docs)]
+//! Functions of the host available to the translator scripts.
+//!
+//! The scripts can look up the dictionaries, convert a text with the memory of the input method,
+//! and read the recent commits.
+
+use crate::dictionary::Dictionary;
+use afrim_memory::{utils, Node};
+use rhai::{Array, Engine};
+use std::{cell::RefCell, rc::Rc};
+
+// The data of the translator shared with the scripts.
+#[derive(Default)]
+pub(crate) struct Host {
+    // The enabled dictionaries sorted by priority, only during a translation.
+    pub(crate) dictionaries: Vec<Rc<Dictionary>>,
+    pub(crate) memory: Option<Rc<Node>>,
+    // The recent commits, from the oldest to the most recent.
+    pub(crate) context: Vec<String>,
+}
+
+// Registers the functions of the host in the engine.
+//
+// * `lookup(code)`: the texts of a code in the dictionaries.
+// * `transliterate(text)`: the text as if it was typed in the input method.
+// * `context()`: the recent commits.
+pub(crate) fn register(engine: &mut Engine, host: &Rc<RefCell<Host>>) {
+    let shared = Rc::clone(host);
+    engine.register_fn("lookup", move |code: &str| -> Array {
+        shared
+            .borrow()
+            .dictionaries
+            .iter()
+            .filter_map(|dictionary| dictionary.entries.get(code))
+            .flatten()
+            .map(|text| text.into())
+            .collect()
+    });
+
+    let shared = Rc::clone(host);
+    engine.register_fn("transliterate", move |text: &str| -> String {
+        match &shared.borrow().memory {
+            Some(memory) => utils::transliterate(Rc::clone(memory), text),
+            None => text.to_owned(),
+        }
+    });
+
+    let shared = Rc::clone(host);
+    engine.register_fn("context", move || -> Array {
+        shared
+            .borrow()
+            .context
+            .iter()
+            .map(|text| text.into())
+            .collect()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_host() {
+        use super::{register, Host};
+        use crate::dictionary::Dictionary;
+        use afrim_memory::utils;
+        use indexmap::IndexMap;
+        use rhai::Engine;
+        use std::{cell::RefCell, rc::Rc};
+
+        let host = Rc::new(RefCell::new(Host::default()));
+        let mut engine = Engine::new();
+        register(&mut engine, &host);
+        let eval = |script: &str| engine.eval::<String>(script).unwrap();
+
+        // Without data.
+        assert_eq!(eval(r#"lookup("hi").len().to_string()"#), "0");
+        assert_eq!(eval(r#"transliterate("af")"#), "af");
+        assert_eq!(eval("context().len().to_string()"), "0");
+
+        // With data.
+        let mut main = IndexMap::new();
+        main.insert("hi".to_owned(), vec!["hello".to_owned()]);
+        let mut user = IndexMap::new();
+        user.insert("hi".to_owned(), vec!["hey".to_owned()]);
+        *host.borrow_mut() = Host {
+            dictionaries: vec![
+                Rc::new(Dictionary::new(user, 1)),
+                Rc::new(Dictionary::new(main, 0)),
+            ],
+            memory: Some(Rc::new(utils::build_map(vec![vec!["af", "ɑ"]]))),
+            context: vec!["hello".to_owned(), "world".to_owned()],
+        };
+        assert_eq!(
+            eval(r#"lookup("hi").reduce(|all, text| all + text, "")"#),
+            "heyhello"
+        );
+        assert_eq!(eval(r#"transliterate("caf")"#), "cɑ");
+        assert_eq!(eval("context()[-1]"), "world");
+    }
+}
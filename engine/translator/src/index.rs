@@ -0,0 +1,204 @@
+#![deny(missing_docs)]
+//! Index of the keys of the dictionary.
+//!
+//! It permits to look up the keys of a big dictionary without scanning all of them.
+
+use indexmap::IndexMap;
+
+// The positions of the keys of a dictionary.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Index {
+    // Sorted by key.
+    sorted: Vec<usize>,
+}
+
+impl Index {
+    // Builds the index of a dictionary.
+    pub(crate) fn new(dictionary: &IndexMap<String, Vec<String>>) -> Self {
+        let mut sorted: Vec<usize> = (0..dictionary.len()).collect();
+        sorted.sort_unstable_by_key(|&position| dictionary.get_index(position).unwrap().0);
+
+        Self { sorted }
+    }
+
+    // Indexes the key added at the given position of the dictionary.
+    pub(crate) fn insert(&mut self, dictionary: &IndexMap<String, Vec<String>>, position: usize) {
+        let key = |position: usize| dictionary.get_index(position).unwrap().0.as_str();
+        let new_key = key(position);
+        let index = self
+            .sorted
+            .partition_point(|&position| key(position) < new_key);
+
+        self.sorted.insert(index, position);
+    }
+
+    // Unindexes the key removed from the given position of the dictionary.
+    //
+    // The positions after it are shifted.
+    pub(crate) fn remove(&mut self, position: usize) {
+        self.sorted.retain(|&other| other != position);
+        self.sorted
+            .iter_mut()
+            .filter(|other| **other > position)
+            .for_each(|other| *other -= 1);
+    }
+
+    // Returns the positions of the keys starting with the input.
+    pub(crate) fn prefixed<'a>(
+        &'a self,
+        dictionary: &'a IndexMap<String, Vec<String>>,
+        input: &'a str,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let key = |position: usize| dictionary.get_index(position).unwrap().0.as_str();
+        let start = self
+            .sorted
+            .partition_point(|&position| key(position) < input);
+
+        self.sorted[start..]
+            .iter()
+            .copied()
+            .take_while(move |&position| key(position).starts_with(input))
+    }
+
+    // Returns the positions of the keys starting with the first character of the input and
+    // within the maximum edit distance of it, with their distance.
+    //
+    // The distance is the Damerau-Levenshtein distance (optimal string alignment) in characters.
+    // The sorted keys are walked like a trie: the rows of the distance matrix are shared by
+    // the keys with a common prefix, and the keys after a prefix too far from the input are
+    // skipped.
+    #[cfg(feature = "fuzzy")]
+    pub(crate) fn similar(
+        &self,
+        dictionary: &IndexMap<String, Vec<String>>,
+        input: &str,
+        max_distance: usize,
+    ) -> Vec<(usize, usize)> {
+        let input: Vec<char> = input.chars().collect();
+        let Some(first_char) = input.first() else {
+            return vec![];
+        };
+        let mut rows: Vec<Vec<usize>> = vec![(0..=input.len()).collect()];
+        let mut previous_key: Vec<char> = vec![];
+        let mut dead_prefix: Option<Vec<char>> = None;
+        let mut similar = vec![];
+
+        for position in self.prefixed(dictionary, &first_char.to_string()) {
+            let key: Vec<char> = dictionary.get_index(position).unwrap().0.chars().collect();
+
+            if dead_prefix
+                .as_ref()
+                .is_some_and(|prefix| key.starts_with(prefix))
+            {
+                continue;
+            }
+            dead_prefix = None;
+
+            // The rows of the common prefix are reused.
+            let common = key
+                .iter()
+                .zip(previous_key.iter())
+                .take_while(|(a, b)| a == b)
+                .count()
+                .min(rows.len() - 1);
+            rows.truncate(common + 1);
+
+            for depth in common..key.len() {
+                let row = Self::next_row(&rows, &key, &input, depth);
+                let is_dead = row.iter().all(|&distance| distance > max_distance);
+
+                rows.push(row);
+                if is_dead {
+                    dead_prefix = Some(key[..=depth].to_vec());
+                    break;
+                }
+            }
+            previous_key = key;
+
+            let distance = rows[rows.len() - 1][input.len()];
+            if dead_prefix.is_none() && distance <= max_distance {
+                similar.push((position, distance));
+            }
+        }
+
+        similar
+    }
+
+    // Computes the row of the distance matrix for the key character at the given depth.
+    #[cfg(feature = "fuzzy")]
+    fn next_row(rows: &[Vec<usize>], key: &[char], input: &[char], depth: usize) -> Vec<usize> {
+        let previous = &rows[depth];
+        let mut row = vec![depth + 1; input.len() + 1];
+
+        for i in 1..=input.len() {
+            let cost = usize::from(key[depth] != input[i - 1]);
+
+            row[i] = (previous[i - 1] + cost)
+                .min(previous[i] + 1)
+                .min(row[i - 1] + 1);
+
+            // Transposition of two adjacent characters.
+            if depth > 0 && i > 1 && key[depth] == input[i - 2] && key[depth - 1] == input[i - 1] {
+                row[i] = row[i].min(rows[depth - 1][i - 2] + 1);
+            }
+        }
+
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_index() {
+        use super::Index;
+        use indexmap::IndexMap;
+
+        let mut dictionary = IndexMap::new();
+        ["jumper", "halo", "jump", "hello", "ju", "jamp"]
+            .into_iter()
+            .for_each(|key| {
+                dictionary.insert(key.to_owned(), vec![]);
+            });
+        let mut index = Index::new(&dictionary);
+
+        assert_eq!(
+            index.prefixed(&dictionary, "jum").collect::<Vec<_>>(),
+            vec![2, 0]
+        );
+        assert_eq!(
+            index.prefixed(&dictionary, "j").collect::<Vec<_>>(),
+            vec![5, 4, 2, 0]
+        );
+        assert_eq!(index.prefixed(&dictionary, "x").count(), 0);
+
+        #[cfg(feature = "fuzzy")]
+        {
+            // Substitution.
+            assert_eq!(index.similar(&dictionary, "jimp", 1), vec![(5, 1), (2, 1)]);
+            // Insertion and deletion.
+            assert_eq!(index.similar(&dictionary, "jumpr", 1), vec![(2, 1), (0, 1)]);
+            assert_eq!(index.similar(&dictionary, "jumpeer", 1), vec![(0, 1)]);
+            // Transposition.
+            assert_eq!(index.similar(&dictionary, "hlelo", 1), vec![(3, 1)]);
+            assert_eq!(index.similar(&dictionary, "hlelo", 2), vec![(1, 2), (3, 1)]);
+            // Characters, not bytes.
+            assert_eq!(index.similar(&dictionary, "hɛllo", 1), vec![(3, 1)]);
+            assert!(index.similar(&dictionary, "xump", 1).is_empty());
+        }
+
+        // Updates of the dictionary.
+        dictionary.insert("jumps".to_owned(), vec![]);
+        index.insert(&dictionary, 6);
+        assert_eq!(
+            index.prefixed(&dictionary, "jum").collect::<Vec<_>>(),
+            vec![2, 0, 6]
+        );
+        dictionary.shift_remove("jump");
+        index.remove(2);
+        assert_eq!(
+            index.prefixed(&dictionary, "jum").collect::<Vec<_>>(),
+            vec![0, 5]
+        );
+    }
+}
@@ -14,13 +14,14 @@
 //!
 //! * `rhai`: Enables the usage of rhai script files.
 //! * `rhai-wasm`: Like rhai, but wasm compatible.
-//! * `strsim`: Enables the text similarity algorithm for better predictions.
+//! * `fuzzy`: Enables the correction of the typos, using the built-in Damerau-Levenshtein index.
+//! * `strsim`: Alias of `fuzzy`, kept for compatibility.
 //! * `serde`: Enables serde feature.
 //!
 //! # Example
 //!
 //! ```
-//! use afrim_translator::{Predicate, Translator};
+//! use afrim_translator::{MatchKind, Predicate, Translator};
 //! use indexmap::IndexMap;
 //!
 //! // Prepares the dictionary.
@@ -39,23 +40,29 @@
 //!             code: "jump".to_owned(),
 //!             remaining_code: "".to_owned(),
 //!             texts: vec!["sauter".to_owned()],
-//!             can_commit: true
+//!             can_commit: true,
+//!             confidence: 1.0,
+//!             kind: MatchKind::Exact,
+//!             source: Some("main".to_owned())
 //!         },
 //!         // Auto-completion.
 //!         Predicate {
 //!             code: "jumper".to_owned(),
 //!             remaining_code: "er".to_owned(),
 //!             texts: vec!["sauteur".to_owned()],
-//!             can_commit: false
+//!             can_commit: false,
+//!             confidence: 0.5,
+//!             kind: MatchKind::Prefix,
+//!             source: Some("main".to_owned())
 //!         }
 //!     ]
 //! );
 //! ```
 //!
-//! # Example with the strsim feature
+//! # Example with the fuzzy feature
 //!
 //! ```
-//! use afrim_translator::{Predicate, Translator};
+//! use afrim_translator::{MatchKind, Predicate, Translator};
 //! use indexmap::IndexMap;
 //!
 //! // Prepares the dictionary.
@@ -67,14 +74,17 @@
 //! let mut translator = Translator::new(dictionary, true);
 //!
 //! // Auto-suggestion / Auto-correction.
-//! #[cfg(feature = "strsim")]
+//! #[cfg(feature = "fuzzy")]
 //! assert_eq!(
 //!     translator.translate("junp"),
 //!     vec![Predicate {
 //!         code: "jump".to_owned(),
 //!         remaining_code: "".to_owned(),
 //!         texts: vec!["sauter".to_owned()],
-//!         can_commit: false
+//!         can_commit: false,
+//!         confidence: 0.75,
+//!         kind: MatchKind::Fuzzy,
+//!         source: Some("main".to_owned())
 //!     }]
 //! );
 //! ```
@@ -84,7 +94,7 @@
 //! ```
 //! #[cfg(feature = "rhai")]
 //! use afrim_translator::Engine;
-//! use afrim_translator::{Translator, Predicate};
+//! use afrim_translator::{MatchKind, Predicate, Translator};
 //! use indexmap::IndexMap;
 //!
 //! // Prepares the dictionary.
@@ -119,7 +129,10 @@
 //!             code: "jump".to_owned(),
 //!             remaining_code: "".to_owned(),
 //!             texts: vec!["sauter".to_owned()],
-//!             can_commit: true
+//!             can_commit: true,
+//!             confidence: 1.0,
+//!             kind: MatchKind::Exact,
+//!             source: Some("main".to_owned())
 //!         },
 //!         #[cfg(feature = "rhai")]
 //!         // Programmable translation.
@@ -127,31 +140,85 @@
 //!             code: "jump".to_owned(),
 //!             remaining_code: "".to_owned(),
 //!             texts: vec!["\n".to_owned()],
-//!             can_commit: false
+//!             can_commit: false,
+//!             confidence: 1.0,
+//!             kind: MatchKind::Script("jump".to_owned()),
+//!             source: None
 //!         },
 //!         // Auto-completion.
 //!         Predicate {
 //!             code: "jumper".to_owned(),
 //!             remaining_code: "er".to_owned(),
 //!             texts: vec!["sauteur".to_owned()],
-//!             can_commit: false
+//!             can_commit: false,
+//!             confidence: 0.5,
+//!             kind: MatchKind::Prefix,
+//!             source: Some("main".to_owned())
 //!         }
 //!     ]
 //! );
 //! ```
 
+mod candidate;
+mod dictionary;
+#[cfg(feature = "rhai")]
+mod host;
+mod index;
+mod pages;
+mod ranking;
+#[cfg(feature = "rhai")]
+mod script;
+
+#[cfg(feature = "rhai")]
+use afrim_memory::Node;
+use candidate::Candidate;
+use dictionary::Dictionary;
+#[cfg(feature = "rhai")]
+use host::Host;
 use indexmap::IndexMap;
+pub use pages::Pages;
+pub use ranking::Ranking;
 #[cfg(feature = "rhai")]
 pub use rhai::Engine;
 #[cfg(feature = "rhai")]
-use rhai::{Array, Scope, AST};
-use std::cmp::Ordering;
-#[cfg(feature = "strsim")]
-use strsim::{self};
+use rhai::{Scope, AST};
+#[cfg(feature = "rhai")]
+use script::Script;
+#[cfg(feature = "rhai")]
+pub use script::{ScriptError, ScriptLimits};
+#[cfg(feature = "fuzzy")]
+use std::collections::HashMap;
+use std::{borrow::Cow, cmp::Reverse, collections::BinaryHeap, rc::Rc};
+#[cfg(feature = "rhai")]
+use std::{cell::RefCell, path::PathBuf};
 
-/// Struct representing the predicate.
+/// The name of the dictionary given to [`Translator::new`].
+pub const MAIN_DICTIONARY: &str = "main";
+
+// The weight of the selections of the user in the ranking of the predicates.
+const RANKING_WEIGHT: f64 = 0.5;
+// The max number of script errors kept until they are taken.
+#[cfg(feature = "rhai")]
+const MAX_ERRORS: usize = 32;
+
+/// How a predicate matches the input.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchKind {
+    /// The code is the input.
+    #[default]
+    Exact,
+    /// The code starts with the input.
+    Prefix,
+    /// The code is the input with its typos corrected.
+    Fuzzy,
+    /// The predicate is generated by the script with the given name.
+    Script(String),
+}
+
+/// Struct representing the predicate.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Predicate {
     /// The predicate code.
     pub code: String,
@@ -161,21 +228,47 @@ pub struct Predicate {
     pub texts: Vec<String>,
     /// Whether the predicate can be commit.
     pub can_commit: bool,
+    /// How well the predicate matches the input, between 0 and 1.
+    ///
+    /// The predicates are sorted by confidence, boosted by the selections of the user.
+    pub confidence: f64,
+    /// How the predicate matches the input.
+    pub kind: MatchKind,
+    /// The name of the dictionary of the predicate, if any.
+    pub source: Option<String>,
 }
 
 /// Core structure of the translator.
 pub struct Translator {
-    dictionary: IndexMap<String, Vec<String>>,
+    // The dictionaries are shared with the scripts during a translation.
+    dictionaries: IndexMap<String, Rc<Dictionary>>,
+    ranking: Ranking,
+    #[cfg(feature = "fuzzy")]
+    max_distance: usize,
+    #[cfg(feature = "fuzzy")]
+    similarity_threshold: f64,
     #[cfg(feature = "rhai")]
-    translators: IndexMap<String, AST>,
+    translators: IndexMap<String, Script>,
     #[cfg(feature = "rhai")]
     engine: Engine,
+    #[cfg(feature = "rhai")]
+    script_limits: ScriptLimits,
+    #[cfg(feature = "rhai")]
+    module_root: Option<PathBuf>,
+    #[cfg(feature = "rhai")]
+    max_failures: Option<usize>,
+    #[cfg(feature = "rhai")]
+    errors: RefCell<Vec<ScriptError>>,
+    #[cfg(feature = "rhai")]
+    host: Rc<RefCell<Host>>,
     auto_commit: bool,
 }
 
 impl Translator {
     /// Initiatializes a new translator.
     ///
+    /// The dictionary is named [`MAIN_DICTIONARY`].
+    ///
     /// # Example
     ///
     /// ```
@@ -186,26 +279,180 @@ impl Translator {
     /// let translator = Translator::new(dictionary, false);
     /// ```
     pub fn new(dictionary: IndexMap<String, Vec<String>>, auto_commit: bool) -> Self {
+        let mut dictionaries = IndexMap::new();
+        dictionaries.insert(
+            MAIN_DICTIONARY.to_owned(),
+            Rc::new(Dictionary::new(dictionary, 0)),
+        );
+        #[cfg(feature = "rhai")]
+        let host = Rc::default();
+
         Self {
-            dictionary,
+            dictionaries,
+            ranking: Ranking::default(),
+            #[cfg(feature = "fuzzy")]
+            max_distance: 2,
+            #[cfg(feature = "fuzzy")]
+            similarity_threshold: 0.7,
             auto_commit,
             #[cfg(feature = "rhai")]
             translators: IndexMap::default(),
             #[cfg(feature = "rhai")]
-            engine: Engine::new(),
+            engine: script::engine(&ScriptLimits::default(), None, &host),
+            #[cfg(feature = "rhai")]
+            script_limits: ScriptLimits::default(),
+            #[cfg(feature = "rhai")]
+            module_root: None,
+            #[cfg(feature = "rhai")]
+            max_failures: Some(3),
+            #[cfg(feature = "rhai")]
+            errors: RefCell::default(),
+            #[cfg(feature = "rhai")]
+            host,
         }
     }
 
+    /// Adds a dictionary with its priority.
+    ///
+    /// A dictionary with the same name is replaced. At equal confidence, the predicates of the
+    /// dictionaries with the highest priority are ranked first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::{Translator, MAIN_DICTIONARY};
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut lexicon = IndexMap::new();
+    /// lexicon.insert("paris".to_owned(), vec!["bets".to_owned()]);
+    /// let mut names = IndexMap::new();
+    /// names.insert("paris".to_owned(), vec!["Paris".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(lexicon, false);
+    /// translator.add_dictionary("names".to_owned(), names, 1);
+    ///
+    /// let predicates = translator.translate("paris");
+    /// assert_eq!(predicates[0].texts, vec!["Paris".to_owned()]);
+    /// assert_eq!(predicates[0].source, Some("names".to_owned()));
+    /// assert_eq!(predicates[1].source, Some(MAIN_DICTIONARY.to_owned()));
+    /// ```
+    pub fn add_dictionary(
+        &mut self,
+        name: String,
+        dictionary: IndexMap<String, Vec<String>>,
+        priority: i32,
+    ) {
+        self.dictionaries
+            .insert(name, Rc::new(Dictionary::new(dictionary, priority)));
+    }
+
+    /// Enables or disables a dictionary.
+    ///
+    /// Returns false if the dictionary doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::{Translator, MAIN_DICTIONARY};
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("hi".to_owned(), vec!["hello".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// assert!(translator.set_dictionary_enabled(MAIN_DICTIONARY, false));
+    /// assert_eq!(translator.translate("hi"), vec![]);
+    ///
+    /// assert!(translator.set_dictionary_enabled(MAIN_DICTIONARY, true));
+    /// assert_eq!(translator.translate("hi").len(), 1);
+    /// assert!(!translator.set_dictionary_enabled("emoji", true));
+    /// ```
+    pub fn set_dictionary_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        self.dictionaries
+            .get_mut(name)
+            .map(|dictionary| Rc::make_mut(dictionary).enabled = enabled)
+            .is_some()
+    }
+
+    /// Returns the names of the dictionaries, and whether they are enabled.
+    pub fn dictionaries(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.dictionaries
+            .iter()
+            .map(|(name, dictionary)| (name.as_str(), dictionary.enabled))
+    }
+
+    /// Adds the texts of a code in the main dictionary.
+    ///
+    /// Returns the previous texts of the code, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut translator = Translator::new(IndexMap::new(), false);
+    /// assert_eq!(translator.insert("hi".to_owned(), vec!["hello".to_owned()]), None);
+    /// assert_eq!(translator.get("hi"), Some(&vec!["hello".to_owned()]));
+    /// assert_eq!(translator.translate("hi")[0].texts, vec!["hello".to_owned()]);
+    ///
+    /// assert_eq!(
+    ///     translator.insert("hi".to_owned(), vec!["hey".to_owned()]),
+    ///     Some(vec!["hello".to_owned()])
+    /// );
+    /// assert_eq!(translator.translate("hi")[0].texts, vec!["hey".to_owned()]);
+    /// ```
+    pub fn insert(&mut self, code: String, texts: Vec<String>) -> Option<Vec<String>> {
+        let dictionary = self
+            .dictionaries
+            .entry(MAIN_DICTIONARY.to_owned())
+            .or_insert_with(|| Rc::new(Dictionary::new(IndexMap::new(), 0)));
+
+        Rc::make_mut(dictionary).insert(code, texts)
+    }
+
+    /// Removes a code from the main dictionary.
+    ///
+    /// Returns the texts of the code, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("hi".to_owned(), vec!["hello".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// assert_eq!(translator.remove("hi"), Some(vec!["hello".to_owned()]));
+    /// assert_eq!(translator.remove("hi"), None);
+    /// assert_eq!(translator.translate("hi"), vec![]);
+    /// ```
+    pub fn remove(&mut self, code: &str) -> Option<Vec<String>> {
+        Rc::make_mut(self.dictionaries.get_mut(MAIN_DICTIONARY)?).remove(code)
+    }
+
+    /// Returns the texts of a code in the main dictionary.
+    pub fn get(&self, code: &str) -> Option<&Vec<String>> {
+        self.dictionaries.get(MAIN_DICTIONARY)?.entries.get(code)
+    }
+
     #[cfg(feature = "rhai")]
     /// Registers a translator.
     ///
     /// The provided name will be used for debugging in case of script error.
     /// Note that the scripts are compiled using [`Engine`](crate::Engine::compile).
     ///
+    /// The `translate` function of a script returns nothing, a predicate or an array of
+    /// predicates. A predicate is either the array `[code, remaining_code, texts, can_commit]`,
+    /// or an object map with the same fields and a `score`. Only the `texts` are required in the
+    /// map, the `code` is the input by default and the `score` is a percentage (100 by default).
+    ///
     /// # Example
     ///
     /// ```
-    /// use afrim_translator::{Engine, Predicate, Translator};
+    /// use afrim_translator::{Engine, MatchKind, Predicate, Translator};
     /// use indexmap::IndexMap;
     ///
     /// // We prepare the script.
@@ -245,6 +492,8 @@ impl Translator {
     ///    }
     /// "#;
     /// let mut engine = Engine::new();
+    /// # // The debug builds of rhai have lower depths.
+    /// # engine.set_max_expr_depths(64, 32);
     /// let date_translator = engine.compile(date_translator).unwrap();
     ///
     /// // We build the translator.
@@ -260,13 +509,43 @@ impl Translator {
     ///             code: "09/02/2024".to_owned(),
     ///             remaining_code: "".to_owned(),
     ///             texts: vec!["9, Feb 2024".to_owned()],
-    ///             can_commit: true
+    ///             can_commit: true,
+    ///             confidence: 1.0,
+    ///             kind: MatchKind::Script("date_translator".to_owned()),
+    ///             source: None
     ///         }
     ///     ]
     /// );
     /// ```
+    ///
+    /// # Example with several predicates
+    ///
+    /// ```
+    /// use afrim_translator::{Engine, Translator};
+    /// use indexmap::IndexMap;
+    ///
+    /// let engine = Engine::new();
+    /// let greetings = engine.compile(r#"
+    ///     fn translate(input) {
+    ///         if input == "hi" {
+    ///             [
+    ///                 #{ texts: ["hey"], score: 40 },
+    ///                 #{ texts: ["hello"], can_commit: true },
+    ///             ]
+    ///         }
+    ///     }
+    /// "#).unwrap();
+    ///
+    /// let mut translator = Translator::new(IndexMap::new(), false);
+    /// translator.register("greetings".to_owned(), greetings);
+    ///
+    /// let predicates = translator.translate("hi");
+    /// assert_eq!(predicates[0].texts, vec!["hello".to_owned()]);
+    /// assert_eq!(predicates[1].texts, vec!["hey".to_owned()]);
+    /// assert_eq!(predicates[1].confidence, 0.4);
+    /// ```
     pub fn register(&mut self, name: String, ast: AST) {
-        self.translators.insert(name, ast);
+        self.translators.insert(name, Script::new(ast));
     }
 
     #[cfg(feature = "rhai")]
@@ -274,7 +553,7 @@ impl Translator {
     ///
     /// # Example
     /// ```
-    /// use afrim_translator::{Engine, Predicate, Translator};
+    /// use afrim_translator::{Engine, MatchKind, Predicate, Translator};
     /// use indexmap::IndexMap;
     ///
     /// // We prepare the script.
@@ -293,7 +572,10 @@ impl Translator {
     ///             code: "hello".to_owned(),
     ///             remaining_code: "".to_owned(),
     ///             texts: vec![],
-    ///             can_commit: true
+    ///             can_commit: true,
+    ///             confidence: 1.0,
+    ///             kind: MatchKind::Script("erase".to_owned()),
+    ///             source: None
     ///         }
     ///     ]
     /// );
@@ -306,13 +588,423 @@ impl Translator {
         self.translators.shift_remove(name);
     }
 
+    #[cfg(feature = "rhai")]
+    /// Sets the resources that the translators can use on each call.
+    ///
+    /// See [`ScriptLimits`] for an example.
+    pub fn set_script_limits(&mut self, limits: ScriptLimits) {
+        self.engine = script::engine(&limits, self.module_root.as_deref(), &self.host);
+        self.script_limits = limits;
+    }
+
+    #[cfg(feature = "rhai")]
+    /// Sets the directory where the translators can import their modules.
+    ///
+    /// The modules outside of it are not found. By default, the imports are not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use afrim_translator::{Engine, Translator};
+    /// use indexmap::IndexMap;
+    /// use std::path::PathBuf;
+    ///
+    /// let engine = Engine::new();
+    /// let script = engine.compile(r#"
+    ///     import "scripts/core" as core;
+    ///
+    ///     fn translate(input) { core::translate(input) }
+    /// "#).unwrap();
+    ///
+    /// let mut translator = Translator::new(IndexMap::new(), false);
+    /// translator.set_module_root(Some(PathBuf::from("./data")));
+    /// translator.register("core".to_owned(), script);
+    /// ```
+    pub fn set_module_root(&mut self, module_root: Option<PathBuf>) {
+        self.engine = script::engine(&self.script_limits, module_root.as_deref(), &self.host);
+        self.module_root = module_root;
+    }
+
+    #[cfg(feature = "rhai")]
+    /// Sets the memory used by the translators to convert a text.
+    ///
+    /// The scripts can call these functions of the translator:
+    /// * `lookup(code)`: returns the texts of a code in the enabled dictionaries, by priority.
+    /// * `transliterate(text)`: returns the text as if it was typed in the input method.
+    /// * `context()`: returns the recent commits, see [`Translator::set_context`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::utils;
+    /// use afrim_translator::{Engine, Translator};
+    /// use indexmap::IndexMap;
+    /// use std::rc::Rc;
+    ///
+    /// let engine = Engine::new();
+    /// let script = engine.compile(r#"
+    ///     fn translate(input) {
+    ///         let texts = [];
+    ///         for text in lookup(input) {
+    ///             texts.push(transliterate(text));
+    ///         }
+    ///
+    ///         let context = context();
+    ///         if !context.is_empty() {
+    ///             texts.push(context[-1] + " " + texts[0]);
+    ///         }
+    ///
+    ///         #{ texts: texts }
+    ///     }
+    /// "#).unwrap();
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("ball".to_owned(), vec!["baf".to_owned()]);
+    /// let memory = utils::build_map(vec![vec!["af", "ɑ"]]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// translator.set_memory(Some(Rc::new(memory)));
+    /// translator.set_context(vec!["one".to_owned()]);
+    /// translator.register("native".to_owned(), script);
+    ///
+    /// let predicates = translator.translate("ball");
+    /// assert_eq!(predicates[1].texts, vec!["bɑ".to_owned(), "one bɑ".to_owned()]);
+    /// ```
+    pub fn set_memory(&mut self, memory: Option<Rc<Node>>) {
+        self.host.borrow_mut().memory = memory;
+    }
+
+    #[cfg(feature = "rhai")]
+    /// Sets the recent commits, from the oldest to the most recent.
+    ///
+    /// They are available to the translators. See [`Translator::set_memory`] for an example.
+    pub fn set_context(&mut self, context: Vec<String>) {
+        self.host.borrow_mut().context = context;
+    }
+
+    #[cfg(feature = "rhai")]
+    /// Sets the number of failures in a row after which a translator is disabled.
+    ///
+    /// A disabled translator is enabled again when it's registered again.
+    /// The default is 3, and `None` never disables them.
+    pub fn set_max_failures(&mut self, max_failures: Option<usize>) {
+        self.max_failures = max_failures;
+    }
+
+    #[cfg(feature = "rhai")]
+    /// Returns the errors raised by the translators since the last call.
+    ///
+    /// Only the most recent errors are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::{Engine, ScriptError, Translator};
+    /// use indexmap::IndexMap;
+    ///
+    /// let engine = Engine::new();
+    /// let script = engine.compile(r#"fn translate(input) { throw "oops" }"#).unwrap();
+    ///
+    /// let mut translator = Translator::new(IndexMap::new(), false);
+    /// translator.register("failing".to_owned(), script);
+    /// translator.set_max_failures(Some(1));
+    ///
+    /// assert_eq!(translator.translate("hello"), vec![]);
+    /// let errors = translator.take_errors();
+    /// assert!(matches!(&errors[0], ScriptError::Runtime { translator, .. } if translator == "failing"));
+    /// assert_eq!(
+    ///     errors[1],
+    ///     ScriptError::Disabled { translator: "failing".to_owned() }
+    /// );
+    ///
+    /// // The translator is no longer run.
+    /// translator.translate("hello");
+    /// assert_eq!(translator.take_errors(), vec![]);
+    /// ```
+    pub fn take_errors(&self) -> Vec<ScriptError> {
+        self.errors.take()
+    }
+
+    #[cfg(feature = "rhai")]
+    // Runs a translator, and reports its errors.
+    fn run_script(
+        &self,
+        name: &str,
+        script: &Script,
+        scope: &mut Scope,
+        input: &str,
+    ) -> Vec<Predicate> {
+        if script.is_disabled(self.max_failures) {
+            return vec![];
+        }
+
+        script
+            .run(name, &self.engine, scope, input)
+            .unwrap_or_else(|error| {
+                self.report(error);
+                if script.is_disabled(self.max_failures) {
+                    self.report(ScriptError::Disabled {
+                        translator: name.to_owned(),
+                    });
+                }
+
+                vec![]
+            })
+    }
+
+    #[cfg(feature = "rhai")]
+    // Keeps an error until it's taken.
+    fn report(&self, error: ScriptError) {
+        let mut errors = self.errors.borrow_mut();
+
+        if errors.len() >= MAX_ERRORS {
+            errors.remove(0);
+        }
+        errors.push(error);
+    }
+
+    #[cfg(feature = "fuzzy")]
+    /// Sets the maximum number of typos corrected in a code.
+    ///
+    /// A typo is a missing, extra, wrong or swapped character. The default is 2.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::{MatchKind, Predicate, Translator};
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("hello".to_owned(), vec!["hi".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// let predicate = Predicate {
+    ///     code: "hello".to_owned(),
+    ///     remaining_code: "".to_owned(),
+    ///     texts: vec!["hi".to_owned()],
+    ///     can_commit: false,
+    ///     confidence: 0.8,
+    ///     kind: MatchKind::Fuzzy,
+    ///     source: Some("main".to_owned()),
+    /// };
+    /// assert_eq!(translator.translate("helo"), vec![predicate.clone()]);
+    /// assert_eq!(translator.translate("hlelo"), vec![predicate]);
+    ///
+    /// translator.set_max_distance(0);
+    /// assert_eq!(translator.translate("helo"), vec![]);
+    /// ```
+    pub fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+
+    #[cfg(feature = "fuzzy")]
+    /// Sets the similarity above which a code with typos is corrected.
+    ///
+    /// The similarity is between 0 and 1, and depends of the number of typos relative to the
+    /// length of the code. The default is 0.7.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("hello".to_owned(), vec!["hi".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// assert_eq!(translator.translate("helo").len(), 1);
+    ///
+    /// translator.set_similarity_threshold(0.9);
+    /// assert_eq!(translator.translate("helo").len(), 0);
+    /// ```
+    pub fn set_similarity_threshold(&mut self, similarity_threshold: f64) {
+        self.similarity_threshold = similarity_threshold;
+    }
+
+    /// Records the selection of a text for a code.
+    ///
+    /// The texts selected often and recently are ranked first by [`Translator::translate`].
+    /// See [`Ranking`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// // We prepare the dictionary.
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("hi".to_owned(), vec!["hello".to_owned(), "hey".to_owned()]);
+    /// dictionary.insert("hiya".to_owned(), vec!["hiya".to_owned()]);
+    /// dictionary.insert("hiker".to_owned(), vec!["walker".to_owned()]);
+    ///
+    /// let mut translator = Translator::new(dictionary, false);
+    /// translator.learn("hiker", "walker");
+    /// translator.learn("hi", "hey");
+    ///
+    /// let predicates = translator.translate("hi");
+    /// assert_eq!(predicates[0].texts, vec!["hey".to_owned(), "hello".to_owned()]);
+    /// assert_eq!(predicates[1].code, "hiker".to_owned());
+    /// assert_eq!(predicates[2].code, "hiya".to_owned());
+    /// // The confidence is not affected.
+    /// assert_eq!(predicates[0].confidence, 1.0);
+    ///
+    /// // We forget what has been learned.
+    /// translator.reset_ranking();
+    /// let predicates = translator.translate("hi");
+    /// assert_eq!(predicates[0].texts, vec!["hello".to_owned(), "hey".to_owned()]);
+    /// assert_eq!(predicates[1].code, "hiya".to_owned());
+    /// ```
+    pub fn learn(&mut self, code: &str, text: &str) {
+        self.ranking.record(code, text);
+    }
+
+    /// Returns the selections learned.
+    ///
+    /// Useful to export them.
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+
+    /// Replaces the selections learned.
+    ///
+    /// Useful to import them.
+    pub fn set_ranking(&mut self, ranking: Ranking) {
+        self.ranking = ranking;
+    }
+
+    /// Forgets the selections learned.
+    pub fn reset_ranking(&mut self) {
+        self.ranking = Ranking::default();
+    }
+
+    // Looks up the input in a dictionary.
+    fn lookup<'a>(
+        &'a self,
+        name: &'a str,
+        dictionary: &'a Dictionary,
+        input: &'a str,
+    ) -> impl Iterator<Item = Candidate<'a>> + 'a {
+        // Cache the value once; it is reused on every loop iteration.
+        let input_len = input.len();
+        #[cfg(feature = "fuzzy")]
+        let input_char_count = input.chars().count();
+
+        // Only the keys which can match the input are considered.
+        let positions = dictionary.index.prefixed(&dictionary.entries, input);
+        #[cfg(feature = "fuzzy")]
+        let similar: HashMap<usize, usize> = dictionary
+            .index
+            .similar(&dictionary.entries, input, self.max_distance)
+            .into_iter()
+            .collect();
+        #[cfg(feature = "fuzzy")]
+        let positions = positions.chain(similar.keys().copied());
+        let mut positions: Vec<usize> = positions.collect();
+        positions.sort_unstable();
+        positions.dedup();
+
+        positions.into_iter().filter_map(move |position| {
+            let (key, values) = dictionary.entries.get_index(position)?;
+            let texts = Cow::Borrowed(values.as_slice());
+
+            // 1. Exact match
+            if key == input {
+                return Some(Candidate::new(
+                    Predicate {
+                        code: key.to_owned(),
+                        remaining_code: String::new(),
+                        texts: vec![],
+                        can_commit: self.auto_commit,
+                        confidence: 1.0,
+                        kind: MatchKind::Exact,
+                        source: Some(name.to_owned()),
+                    },
+                    texts,
+                    dictionary.priority,
+                ));
+            }
+
+            // 2. Prefix completion
+            if key.starts_with(input) {
+                return Some(Candidate::new(
+                    Predicate {
+                        code: key.to_owned(),
+                        // `starts_with` guarantees `input_len` is a valid UTF-8 char
+                        // boundary in `key`, so the byte slice is correct and O(1).
+                        remaining_code: key[input_len..].to_owned(),
+                        texts: vec![],
+                        can_commit: false,
+                        confidence: 0.5,
+                        kind: MatchKind::Prefix,
+                        source: Some(name.to_owned()),
+                    },
+                    texts,
+                    dictionary.priority,
+                ));
+            }
+
+            // 3. Fuzzy correction of the typos
+            #[cfg(feature = "fuzzy")]
+            if let Some(&distance) = similar.get(&position) {
+                let len = key.chars().count().max(input_char_count);
+                let confidence = 1.0 - (distance as f64 / len as f64);
+
+                if confidence > self.similarity_threshold {
+                    return Some(Candidate::new(
+                        Predicate {
+                            code: key.to_owned(),
+                            remaining_code: String::new(),
+                            texts: vec![],
+                            can_commit: false,
+                            confidence,
+                            kind: MatchKind::Fuzzy,
+                            source: Some(name.to_owned()),
+                        },
+                        texts,
+                        dictionary.priority,
+                    ));
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Returns a cursor over the pages of the predicates of the input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("salade".to_owned(), vec!["vegetable".to_owned()]);
+    /// dictionary.insert("sal".to_owned(), vec!["dirty".to_owned()]);
+    /// dictionary.insert("salut!".to_owned(), vec!["hello!".to_owned()]);
+    ///
+    /// let translator = Translator::new(dictionary, false);
+    /// let mut pages = translator.pages("sal", 2);
+    /// assert_eq!(pages.next().map(|page| page.len()), Some(2));
+    /// assert_eq!(pages.page(), 1);
+    ///
+    /// let page = pages.next().unwrap();
+    /// assert_eq!(page[0].code, "salut!".to_owned());
+    /// assert_eq!(pages.next(), None);
+    /// ```
+    pub fn pages<'a>(&'a self, input: &'a str, page_size: usize) -> Pages<'a> {
+        Pages::new(self, input, page_size)
+    }
+
     /// Generates a list of predicates based on the input.
     ///
     /// # Example
     ///
     /// ```
     /// use indexmap::IndexMap;
-    /// use afrim_translator::{Predicate, Translator};
+    /// use afrim_translator::{MatchKind, Predicate, Translator};
     ///
     /// // We prepares the dictionary.
     /// let mut dictionary = IndexMap::new();
@@ -328,139 +1020,129 @@ impl Translator {
     ///             code: "salut!".to_owned(),
     ///             remaining_code: "ut!".to_owned(),
     ///             texts: vec!["hello!".to_owned(), "hi!".to_owned()],
-    ///             can_commit: false
+    ///             can_commit: false,
+    ///             confidence: 0.5,
+    ///             kind: MatchKind::Prefix,
+    ///             source: Some("main".to_owned())
     ///         },
     ///         Predicate {
     ///             code: "salade".to_owned(),
     ///             remaining_code: "ade".to_owned(),
     ///             texts: vec!["vegetable".to_owned()],
-    ///             can_commit: false
+    ///             can_commit: false,
+    ///             confidence: 0.5,
+    ///             kind: MatchKind::Prefix,
+    ///             source: Some("main".to_owned())
     ///         }
     ///     ]
     /// )
     /// ```
     pub fn translate(&self, input: &str) -> Vec<Predicate> {
+        self.translate_top_k(input, usize::MAX)
+    }
+
+    /// Generates the k best predicates based on the input.
+    ///
+    /// Same as [`Translator::translate`], but the other predicates are never built.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_translator::Translator;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut dictionary = IndexMap::new();
+    /// dictionary.insert("salade".to_owned(), vec!["vegetable".to_owned()]);
+    /// dictionary.insert("sal".to_owned(), vec!["dirty".to_owned()]);
+    /// dictionary.insert("salut!".to_owned(), vec!["hello!".to_owned()]);
+    ///
+    /// let translator = Translator::new(dictionary, false);
+    /// let predicates = translator.translate_top_k("sal", 2);
+    /// assert_eq!(predicates.len(), 2);
+    /// assert_eq!(predicates[0].code, "sal".to_owned());
+    /// assert_eq!(predicates[1].code, "salade".to_owned());
+    /// ```
+    pub fn translate_top_k(&self, input: &str, k: usize) -> Vec<Predicate> {
+        // Only the k best candidates are kept, the texts of the others are never cloned.
+        // The worst candidate kept is at the top of the heap.
+        let mut heap = BinaryHeap::new();
+        self.for_each_candidate(input, |candidate| {
+            heap.push(Reverse(candidate));
+            if heap.len() > k {
+                heap.pop();
+            }
+        });
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(candidate)| candidate.into_predicate(&self.ranking))
+            .collect()
+    }
+
+    // Generates the candidates of the input, with the boost of the ranking.
+    //
+    // The scripts are run once.
+    pub(crate) fn for_each_candidate<'a>(
+        &'a self,
+        input: &'a str,
+        mut f: impl FnMut(Candidate<'a>),
+    ) {
         // Short-circuit after the 2nd char rather than scanning the whole string
         // with .chars().count() -> O(1) vs O(n).
         {
             let mut ch = input.chars();
             if ch.next().is_none() || ch.next().is_none() {
-                return vec![];
+                return;
             }
         }
 
-        // Cache both values once; they are reused on every loop iteration.
-        let input_len = input.len();
-        // We confirmed at least 2 chars exist above.
-        let input_first_char = input.chars().next().unwrap();
-
         #[cfg(feature = "rhai")]
         let mut scope = Scope::new();
+        // The scripts can look up the enabled dictionaries.
+        #[cfg(feature = "rhai")]
+        if !self.translators.is_empty() {
+            let mut dictionaries: Vec<_> = self
+                .dictionaries
+                .values()
+                .filter(|dictionary| dictionary.enabled)
+                .cloned()
+                .collect();
+            dictionaries.sort_by_key(|dictionary| Reverse(dictionary.priority));
+            self.host.borrow_mut().dictionaries = dictionaries;
+        }
 
-        let predicates = self.dictionary.iter().filter_map(|(key, values)| {
-            let key_len = key.len();
-
-            if input_len > key_len || !key.starts_with(input_first_char) {
-                return None;
-            }
+        // Only the enabled dictionaries are considered.
+        let candidates = self
+            .dictionaries
+            .iter()
+            .filter(|(_, dictionary)| dictionary.enabled)
+            .flat_map(|(name, dictionary)| self.lookup(name, dictionary, input));
 
-            // 1. Exact match
-            if key == input {
-                return Some((
-                    1.0_f64,
-                    Predicate {
-                        code: key.to_owned(),
-                        remaining_code: String::new(),
-                        texts: values.to_owned(),
-                        can_commit: self.auto_commit,
-                    },
-                ));
-            }
-
-            // 2. Fuzzy correction via strsim (same byte-length)
-            #[cfg(feature = "strsim")]
-            if key_len == input_len {
-                let confidence = strsim::hamming(key.as_ref(), input)
-                    .map(|n| 1.0 - (n as f64 / key_len as f64))
-                    .unwrap_or(0.0);
+        #[cfg(feature = "rhai")]
+        let candidates = candidates.chain(self.translators.iter().flat_map(|(name, script)| {
+            self.run_script(name, script, &mut scope, input)
+                .into_iter()
+                .map(|mut predicate| {
+                    let texts = std::mem::take(&mut predicate.texts);
 
-                if confidence > 0.7 {
-                    return Some((
-                        confidence,
-                        Predicate {
-                            code: key.to_owned(),
-                            remaining_code: String::new(),
-                            texts: values.to_owned(),
-                            can_commit: false,
-                        },
-                    ));
-                }
-            }
+                    Candidate::new(predicate, Cow::Owned(texts), 0)
+                })
+        }));
 
-            // 3. Prefix completion
-            if key.starts_with(input) {
-                return Some((
-                    0.5,
-                    Predicate {
-                        code: key.to_owned(),
-                        // `starts_with` guarantees `input_len` is a valid UTF-8 char
-                        // boundary in `key`, so the byte slice is correct and O(1).
-                        remaining_code: key[input_len..].to_owned(),
-                        texts: values.to_owned(),
-                        can_commit: false,
-                    },
-                ));
-            }
+        candidates.enumerate().for_each(|(order, mut candidate)| {
+            // The selections of the user are combined with the confidence.
+            candidate.order = order;
+            candidate.boost = RANKING_WEIGHT
+                * self
+                    .ranking
+                    .best_score(&candidate.predicate.code, &candidate.texts);
 
-            None
+            f(candidate);
         });
 
+        // The dictionaries are no longer shared, they can be edited without copy.
         #[cfg(feature = "rhai")]
-        let predicates =
-            predicates.chain(self.translators.iter().filter_map(|(_name, translator)| {
-                let mut data = self
-                    .engine
-                    .call_fn::<Array>(&mut scope, translator, "translate", (input.to_owned(),))
-                    .unwrap_or_default();
-
-                (data.len() == 4).then(|| {
-                    let code = data.remove(0).into_string().unwrap();
-                    let remaining_code = data.remove(0).into_string().unwrap();
-                    let value = data.remove(0);
-                    let values = if value.is_array() {
-                        value.into_array().unwrap()
-                    } else {
-                        vec![value]
-                    };
-                    let values = values
-                        .into_iter()
-                        .map(|e| e.into_string().unwrap())
-                        .collect();
-                    let translated = data.remove(0).as_bool().unwrap();
-
-                    (
-                        1.0_f64,
-                        Predicate {
-                            code,
-                            remaining_code,
-                            texts: values,
-                            can_commit: translated,
-                        },
-                    )
-                })
-            }));
-
-        let mut predicates: Vec<(f64, Predicate)> = predicates.collect();
-
-        // `sort_unstable_by` avoids the auxiliary allocation that the stable
-        // sort needs for its merge passes; equal-confidence predicates may be
-        // reordered, but their relative priority is identical so it's safe.
-        predicates.sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
-
-        predicates
-            .into_iter()
-            .map(|(_, predicate)| predicate)
-            .collect()
+        self.host.borrow_mut().dictionaries.clear();
     }
 }
 
@@ -470,7 +1152,7 @@ mod tests {
     fn test_translate() {
         #[cfg(feature = "rhai")]
         use crate::Engine;
-        use crate::{Predicate, Translator};
+        use crate::{MatchKind, Predicate, Translator};
         use indexmap::IndexMap;
 
         // We build the translation
@@ -514,7 +1196,10 @@ mod tests {
                 code: "hi".to_owned(),
                 remaining_code: "".to_owned(),
                 texts: vec!["hello".to_owned()],
-                can_commit: true
+                can_commit: true,
+                confidence: 1.0,
+                kind: MatchKind::Script("some".to_owned()),
+                source: None
             }]
         );
         assert_eq!(
@@ -523,17 +1208,36 @@ mod tests {
                 code: "halo".to_owned(),
                 remaining_code: "lo".to_owned(),
                 texts: vec!["hello".to_owned()],
-                can_commit: false
+                can_commit: false,
+                confidence: 0.5,
+                kind: MatchKind::Prefix,
+                source: Some("main".to_owned())
+            }]
+        );
+        #[cfg(feature = "fuzzy")]
+        assert_eq!(
+            translator.translate("hallo"),
+            vec![Predicate {
+                code: "halo".to_owned(),
+                remaining_code: "".to_owned(),
+                texts: vec!["hello".to_owned()],
+                can_commit: false,
+                confidence: 0.8,
+                kind: MatchKind::Fuzzy,
+                source: Some("main".to_owned())
             }]
         );
-        #[cfg(feature = "strsim")]
+        #[cfg(feature = "fuzzy")]
         assert_eq!(
             translator.translate("helo"),
             vec![Predicate {
                 code: "halo".to_owned(),
                 remaining_code: "".to_owned(),
                 texts: vec!["hello".to_owned()],
-                can_commit: false
+                can_commit: false,
+                confidence: 0.75,
+                kind: MatchKind::Fuzzy,
+                source: Some("main".to_owned())
             }]
         );
     }
@@ -0,0 +1,96 @@
+#![deny(missing_docs)]
+//! Pagination of the predicates.
+
+use crate::{candidate::Candidate, Predicate, Translator};
+use std::vec::IntoIter;
+
+/// A cursor over the pages of the predicates of an input.
+///
+/// The input is translated once, at the first page, and the texts are cloned only for the
+/// pages requested.
+/// See [`Translator::pages`] for an example.
+pub struct Pages<'a> {
+    translator: &'a Translator,
+    input: &'a str,
+    page_size: usize,
+    page: usize,
+    // The candidates not yet paged, from the best to the worst.
+    candidates: Option<IntoIter<Candidate<'a>>>,
+}
+
+impl<'a> Pages<'a> {
+    // Initializes a cursor at the first page.
+    pub(crate) fn new(translator: &'a Translator, input: &'a str, page_size: usize) -> Self {
+        Self {
+            translator,
+            input,
+            page_size,
+            page: 0,
+            candidates: None,
+        }
+    }
+
+    /// Returns the index of the next page.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+}
+
+impl Iterator for Pages<'_> {
+    type Item = Vec<Predicate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let translator = self.translator;
+        let candidates = self.candidates.get_or_insert_with(|| {
+            let mut candidates = vec![];
+            translator.for_each_candidate(self.input, |candidate| candidates.push(candidate));
+            candidates.sort_by(|a, b| b.cmp(a));
+
+            candidates.into_iter()
+        });
+        let predicates: Vec<Predicate> = candidates
+            .by_ref()
+            .take(self.page_size)
+            .map(|candidate| candidate.into_predicate(&translator.ranking))
+            .collect();
+
+        if predicates.is_empty() {
+            return None;
+        }
+        self.page += 1;
+
+        Some(predicates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "rhai")]
+    fn test_pages() {
+        use crate::{Engine, ScriptError, Translator};
+
+        let engine = Engine::new();
+        let script = engine
+            .compile(r#"fn translate(input) { throw "oops" }"#)
+            .unwrap();
+        let dictionary = (0..10)
+            .map(|index| (format!("hi{index}"), vec![format!("hello {index}")]))
+            .collect();
+        let mut translator = Translator::new(dictionary, false);
+        translator.register("failing".to_owned(), script);
+
+        // The script is run once for all the pages.
+        let pages: Vec<_> = translator.pages("hi", 2).collect();
+        assert_eq!(pages.len(), 5);
+        assert_eq!(pages[4][1].code, "hi9".to_owned());
+        let errors = translator.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0], ScriptError::Runtime { .. }));
+
+        // The script is not disabled by the pagination.
+        let mut pages = translator.pages("hi", 2);
+        assert_eq!(pages.nth(3).map(|page| page.len()), Some(2));
+        assert_eq!(translator.take_errors().len(), 1);
+    }
+}
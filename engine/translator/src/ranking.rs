@@ -0,0 +1,178 @@
+#![deny(missing_docs)]
+//! Ranking of the predicates according to the selections of the user.
+//!
+//! Each selection of a text for a code is recorded. The texts selected often and recently are
+//! ranked first.
+
+use crate::Predicate;
+use indexmap::IndexMap;
+
+// The number of selections after which the weight of a selection is halved.
+const HALF_LIFE: f64 = 100.0;
+// The number of selections after which a selection is forgotten, its weight being below 0.1%.
+const FORGOTTEN_AGE: u64 = 1000;
+
+/// The selections learned from the user.
+///
+/// With the `serde` feature, it can be saved and restored later.
+///
+/// # Example
+///
+/// ```
+/// use afrim_translator::Ranking;
+///
+/// let mut ranking = Ranking::new();
+/// ranking.record("hi", "hello");
+/// ranking.record("hi", "hey");
+/// ranking.record("hi", "hey");
+///
+/// assert!(ranking.score("hi", "hey") > ranking.score("hi", "hello"));
+/// assert_eq!(ranking.score("hi", "bye"), 0.0);
+/// assert_eq!(ranking.selections(), 3);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ranking {
+    // The number of selections recorded, used as clock for the recency.
+    selections: u64,
+    usages: IndexMap<String, IndexMap<String, Usage>>,
+}
+
+// The usage of a text for a code.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Usage {
+    count: u64,
+    last_selection: u64,
+}
+
+impl Ranking {
+    /// Initializes an empty ranking.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the selection of a text for a code.
+    pub fn record(&mut self, code: &str, text: &str) {
+        self.selections += 1;
+
+        let usage = self
+            .usages
+            .entry(code.to_owned())
+            .or_default()
+            .entry(text.to_owned())
+            .or_default();
+        usage.count += 1;
+        usage.last_selection = self.selections;
+
+        if self.selections.is_multiple_of(FORGOTTEN_AGE) {
+            self.forget();
+        }
+    }
+
+    // Removes the selections too old to affect the ranking.
+    fn forget(&mut self) {
+        let selections = self.selections;
+
+        self.usages.retain(|_, usages| {
+            usages.retain(|_, usage| selections - usage.last_selection < FORGOTTEN_AGE);
+
+            !usages.is_empty()
+        });
+    }
+
+    /// Returns the number of selections recorded.
+    pub fn selections(&self) -> u64 {
+        self.selections
+    }
+
+    /// Returns the score of a text for a code, between 0 and 1.
+    ///
+    /// The score grows with the number of selections and decreases with their age.
+    pub fn score(&self, code: &str, text: &str) -> f64 {
+        let Some(usage) = self.usages.get(code).and_then(|usages| usages.get(text)) else {
+            return 0.0;
+        };
+        let frequency = 1.0 - 1.0 / (1.0 + usage.count as f64);
+        let age = self.selections.saturating_sub(usage.last_selection) as f64;
+
+        frequency * 0.5_f64.powf(age / HALF_LIFE)
+    }
+
+    /// Returns true if no selection has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.usages.is_empty()
+    }
+
+    // Returns the best score of the texts of a code.
+    pub(crate) fn best_score(&self, code: &str, texts: &[String]) -> f64 {
+        if !self.usages.contains_key(code) {
+            return 0.0;
+        }
+
+        texts
+            .iter()
+            .map(|text| self.score(code, text))
+            .fold(0.0, f64::max)
+    }
+
+    // Sorts the texts of a predicate by score.
+    pub(crate) fn sort(&self, predicate: &mut Predicate) {
+        if !self.usages.contains_key(&predicate.code) {
+            return;
+        }
+        let code = &predicate.code;
+
+        // The texts with the same score keep their order.
+        predicate
+            .texts
+            .sort_by(|a, b| self.score(code, b).total_cmp(&self.score(code, a)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_ranking() {
+        use crate::{Predicate, Ranking};
+
+        let mut ranking = Ranking::new();
+        assert!(ranking.is_empty());
+
+        // The frequency.
+        ranking.record("hi", "hey");
+        ranking.record("hi", "hello");
+        ranking.record("hi", "hello");
+        assert!(!ranking.is_empty());
+        assert!(ranking.score("hi", "hello") > ranking.score("hi", "hey"));
+
+        // The recency.
+        (0..200).for_each(|_| ranking.record("bye", "goodbye"));
+        ranking.record("hi", "hey");
+        assert!(ranking.score("hi", "hey") > ranking.score("hi", "hello"));
+        assert!(ranking.score("bye", "goodbye") < 1.0);
+
+        // The texts of a predicate.
+        let mut predicate = Predicate {
+            code: "hi".to_owned(),
+            texts: vec!["hallo".to_owned(), "hello".to_owned(), "hey".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            ranking.best_score(&predicate.code, &predicate.texts),
+            ranking.score("hi", "hey")
+        );
+        ranking.sort(&mut predicate);
+        assert_eq!(
+            predicate.texts,
+            vec!["hey".to_owned(), "hello".to_owned(), "hallo".to_owned()]
+        );
+
+        assert_eq!(ranking.best_score("hallo", &predicate.texts), 0.0);
+
+        // The old selections are forgotten.
+        (0..2000).for_each(|_| ranking.record("bye", "goodbye"));
+        assert!(!ranking.usages.contains_key("hi"));
+        assert_eq!(ranking.usages["bye"].len(), 1);
+    }
+}
@@ -0,0 +1,656 @@
+#![deny(missing_docs)]
+//! Execution of the translators written in rhai.
+//!
+//! The result of a script is validated, and its failures are reported as [`ScriptError`]
+//! instead of crashing the translator. The scripts are run within the [`ScriptLimits`], and can
+//! only import the modules inside the module root.
+
+use crate::host::{self, Host};
+use crate::{MatchKind, Predicate};
+use rhai::{
+    module_resolvers::DummyModuleResolver, Array, Dynamic, Engine, EvalAltResult, Map, Scope, AST,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use rhai::{module_resolvers::FileModuleResolver, Module, ModuleResolver, Position};
+use std::{
+    cell::{Cell, RefCell},
+    error, fmt,
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{path::PathBuf, time::Instant};
+
+// The number of operations between two checks of the duration of a script.
+#[cfg(not(target_arch = "wasm32"))]
+const DURATION_CHECK_INTERVAL: u64 = 64;
+
+/// The resources that a translator script can use on each call.
+///
+/// A script exceeding them is aborted with [`ScriptError::Aborted`].
+///
+/// # Example
+///
+/// ```
+/// use afrim_translator::{Engine, ScriptError, ScriptLimits, Translator};
+/// use indexmap::IndexMap;
+///
+/// let engine = Engine::new();
+/// let script = engine.compile("fn translate(input) { loop {} }").unwrap();
+///
+/// let mut translator = Translator::new(IndexMap::new(), false);
+/// translator.set_script_limits(ScriptLimits {
+///     max_operations: 1_000,
+///     ..Default::default()
+/// });
+/// translator.register("endless".to_owned(), script);
+///
+/// assert_eq!(translator.translate("hello"), vec![]);
+/// assert!(matches!(
+///     &translator.take_errors()[0],
+///     ScriptError::Aborted { translator, .. } if translator == "endless"
+/// ));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptLimits {
+    /// The max number of operations.
+    pub max_operations: u64,
+    /// The max duration of a call.
+    ///
+    /// It's not enforced on wasm.
+    pub max_duration: Duration,
+    /// The max length of a string.
+    pub max_string_size: usize,
+    /// The max length of an array.
+    pub max_array_size: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 100_000,
+            max_duration: Duration::from_millis(100),
+            max_string_size: 4096,
+            max_array_size: 1024,
+        }
+    }
+}
+
+/// An error raised by a translator script.
+///
+/// See [`Translator::take_errors`] for an example.
+///
+/// [`Translator::take_errors`]: crate::Translator::take_errors
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptError {
+    /// The script failed to run.
+    Runtime {
+        /// The name of the translator.
+        translator: String,
+        /// The error raised by the script.
+        message: String,
+    },
+    /// The script returned a value which is not a predicate.
+    InvalidResult {
+        /// The name of the translator.
+        translator: String,
+        /// What is wrong with the value.
+        message: String,
+    },
+    /// The script has been aborted because it exceeded its limits.
+    Aborted {
+        /// The name of the translator.
+        translator: String,
+        /// The exceeded limit.
+        message: String,
+    },
+    /// The script has been disabled after too many failures in a row.
+    Disabled {
+        /// The name of the translator.
+        translator: String,
+    },
+}
+
+impl ScriptError {
+    /// Returns the name of the translator which raised the error.
+    pub fn translator(&self) -> &str {
+        match self {
+            Self::Runtime { translator, .. }
+            | Self::InvalidResult { translator, .. }
+            | Self::Aborted { translator, .. }
+            | Self::Disabled { translator } => translator,
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Runtime {
+                translator,
+                message,
+            } => write!(f, "The translator {translator:?} failed: {message}"),
+            Self::InvalidResult {
+                translator,
+                message,
+            } => write!(
+                f,
+                "The translator {translator:?} returned an invalid result: {message}"
+            ),
+            Self::Aborted {
+                translator,
+                message,
+            } => write!(
+                f,
+                "The translator {translator:?} has been aborted: {message}"
+            ),
+            Self::Disabled { translator } => write!(
+                f,
+                "The translator {translator:?} has been disabled after too many failures"
+            ),
+        }
+    }
+}
+
+impl error::Error for ScriptError {}
+
+// Builds an engine which aborts the scripts exceeding the limits, and which only imports the
+// modules inside the module root. The functions of the host are registered in it.
+//
+// Without module root, the imports are not allowed.
+pub(crate) fn engine(
+    limits: &ScriptLimits,
+    module_root: Option<&Path>,
+    host: &Rc<RefCell<Host>>,
+) -> Engine {
+    let mut engine = Engine::new();
+    host::register(&mut engine, host);
+
+    engine
+        .set_max_operations(limits.max_operations)
+        .set_max_string_size(limits.max_string_size)
+        .set_max_array_size(limits.max_array_size)
+        // The debug builds of rhai have lower depths, used to compile the imported modules.
+        .set_max_expr_depths(64, 32)
+        .set_module_resolver(DummyModuleResolver::new());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // The operations are counted from the start of each call.
+        let max_duration = limits.max_duration;
+        let started = Cell::new(Instant::now());
+        engine.on_progress(move |operations| {
+            if operations == 1 {
+                started.set(Instant::now());
+            }
+
+            (operations % DURATION_CHECK_INTERVAL == 0 && started.get().elapsed() > max_duration)
+                .then(|| format!("Time limit of {max_duration:?} exceeded").into())
+        });
+
+        if let Some(module_root) = module_root {
+            engine.set_module_resolver(SandboxResolver::new(module_root));
+        }
+    }
+
+    engine
+}
+
+// Resolves the imports of the scripts, but only inside the root directory.
+#[cfg(not(target_arch = "wasm32"))]
+struct SandboxResolver {
+    root: PathBuf,
+    files: FileModuleResolver,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SandboxResolver {
+    // Initializes a resolver confined in the root directory.
+    fn new(root: &Path) -> Self {
+        Self {
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            files: FileModuleResolver::new(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ModuleResolver for SandboxResolver {
+    fn resolve(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Rc<Module>, Box<EvalAltResult>> {
+        let file_path = self
+            .files
+            .get_file_path(path, source.and_then(|source| Path::new(source).parent()));
+
+        // The links and the ".." are resolved before the check.
+        match file_path.canonicalize() {
+            Ok(file_path) if file_path.starts_with(&self.root) => {
+                self.files
+                    .resolve(engine, None, &file_path.to_string_lossy(), pos)
+            }
+            _ => Err(EvalAltResult::ErrorModuleNotFound(path.to_owned(), pos).into()),
+        }
+    }
+}
+
+// A registered translator.
+pub(crate) struct Script {
+    pub(crate) ast: AST,
+    // The number of failures in a row.
+    failures: Cell<usize>,
+}
+
+impl Script {
+    // Initializes a script.
+    pub(crate) fn new(ast: AST) -> Self {
+        Self {
+            ast,
+            failures: Cell::new(0),
+        }
+    }
+
+    // Returns true if the script failed too many times in a row.
+    pub(crate) fn is_disabled(&self, max_failures: Option<usize>) -> bool {
+        max_failures.is_some_and(|max_failures| self.failures.get() >= max_failures)
+    }
+
+    // Runs the script on the input, and returns its predicates.
+    //
+    // The failures in a row are counted.
+    pub(crate) fn run(
+        &self,
+        name: &str,
+        engine: &Engine,
+        scope: &mut Scope,
+        input: &str,
+    ) -> Result<Vec<Predicate>, ScriptError> {
+        let result = engine
+            .call_fn::<Dynamic>(scope, &self.ast, "translate", (input.to_owned(),))
+            .map_err(|err| {
+                let translator = name.to_owned();
+                let message = err.to_string();
+
+                match err.unwrap_inner() {
+                    EvalAltResult::ErrorTerminated(reason, ..) => ScriptError::Aborted {
+                        translator,
+                        message: reason.to_string(),
+                    },
+                    EvalAltResult::ErrorTooManyOperations(..)
+                    | EvalAltResult::ErrorTooManyVariables(..)
+                    | EvalAltResult::ErrorTooManyModules(..)
+                    | EvalAltResult::ErrorStackOverflow(..)
+                    | EvalAltResult::ErrorDataTooLarge(..) => ScriptError::Aborted {
+                        translator,
+                        message,
+                    },
+                    _ => ScriptError::Runtime {
+                        translator,
+                        message,
+                    },
+                }
+            })
+            .and_then(|value| {
+                parse(value, input).map_err(|message| ScriptError::InvalidResult {
+                    translator: name.to_owned(),
+                    message,
+                })
+            });
+
+        match result {
+            Ok(_) => self.failures.set(0),
+            Err(_) => self.failures.set(self.failures.get() + 1),
+        }
+
+        result.map(|predicates| {
+            predicates
+                .into_iter()
+                .map(|predicate| Predicate {
+                    kind: MatchKind::Script(name.to_owned()),
+                    ..predicate
+                })
+                .collect()
+        })
+    }
+}
+
+// Converts the value returned by a script into predicates.
+//
+// The expected value is nothing, a predicate, or an array of predicates written as object maps.
+// A predicate is an object map, or the array `[code, remaining_code, text or [texts], can_commit]`.
+fn parse(value: Dynamic, input: &str) -> Result<Vec<Predicate>, String> {
+    if value.is_unit() {
+        return Ok(vec![]);
+    }
+    if value.is_map() {
+        return parse_map(value.cast(), input).map(|predicate| vec![predicate]);
+    }
+    let data = value
+        .into_array()
+        .map_err(|type_name| format!("expected an array or a map, found {type_name}"))?;
+
+    if data.first().is_some_and(Dynamic::is_map) {
+        return data
+            .into_iter()
+            .map(|value| {
+                let type_name = value.type_name();
+
+                value
+                    .try_cast()
+                    .ok_or_else(|| format!("expected a map, found {type_name}"))
+                    .and_then(|map| parse_map(map, input))
+            })
+            .collect();
+    }
+
+    parse_array(data).map(|predicate| predicate.into_iter().collect())
+}
+
+// Converts `[code, remaining_code, text or [texts], can_commit]` into a predicate.
+fn parse_array(data: Array) -> Result<Option<Predicate>, String> {
+    if data.is_empty() {
+        return Ok(None);
+    }
+    let [code, remaining_code, texts, can_commit]: [Dynamic; 4] = data
+        .try_into()
+        .map_err(|data: Vec<Dynamic>| format!("expected 4 elements, found {}", data.len()))?;
+
+    Ok(Some(Predicate {
+        code: string(code, "code")?,
+        remaining_code: string(remaining_code, "remaining_code")?,
+        texts: parse_texts(texts)?,
+        can_commit: boolean(can_commit, "can_commit")?,
+        confidence: 1.0,
+        ..Default::default()
+    }))
+}
+
+// Converts an object map into a predicate.
+//
+// Only the texts are required. The code is the input by default, and the score is a percentage.
+fn parse_map(mut map: Map, input: &str) -> Result<Predicate, String> {
+    let texts = map
+        .remove("texts")
+        .ok_or_else(|| "expected the field texts".to_owned())
+        .and_then(parse_texts)?;
+    let code = map
+        .remove("code")
+        .map(|code| string(code, "code"))
+        .transpose()?
+        .unwrap_or_else(|| input.to_owned());
+    let remaining_code = map
+        .remove("remaining_code")
+        .map(|remaining_code| string(remaining_code, "remaining_code"))
+        .transpose()?
+        .unwrap_or_default();
+    let can_commit = map
+        .remove("can_commit")
+        .map(|can_commit| boolean(can_commit, "can_commit"))
+        .transpose()?
+        .unwrap_or(false);
+    let score = map
+        .remove("score")
+        .map(|score| {
+            score
+                .as_int()
+                .ok()
+                .filter(|score| (0..=100).contains(score))
+                .ok_or_else(|| format!("expected a score between 0 and 100, found {score}"))
+        })
+        .transpose()?
+        .unwrap_or(100);
+
+    if let Some(field) = map.keys().next() {
+        return Err(format!("unknown field {field}"));
+    }
+
+    Ok(Predicate {
+        code,
+        remaining_code,
+        texts,
+        can_commit,
+        confidence: f64::from(score) / 100.0,
+        ..Default::default()
+    })
+}
+
+// Converts a text or an array of texts.
+fn parse_texts(texts: Dynamic) -> Result<Vec<String>, String> {
+    if texts.is_array() {
+        texts
+            .into_array()
+            .unwrap()
+            .into_iter()
+            .map(|text| string(text, "text"))
+            .collect()
+    } else {
+        Ok(vec![string(texts, "text")?])
+    }
+}
+
+// Converts a string field.
+fn string(value: Dynamic, field: &str) -> Result<String, String> {
+    value
+        .into_string()
+        .map_err(|type_name| format!("expected a string as {field}, found {type_name}"))
+}
+
+// Converts a boolean field.
+fn boolean(value: Dynamic, field: &str) -> Result<bool, String> {
+    value
+        .as_bool()
+        .map_err(|type_name| format!("expected a boolean as {field}, found {type_name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_script() {
+        use super::{Script, ScriptError};
+        use crate::{Engine, MatchKind, Predicate};
+        use rhai::Scope;
+
+        let engine = Engine::new();
+        let mut scope = Scope::new();
+        let script = |body: &str| Script::new(engine.compile(body).unwrap());
+
+        // Valid results.
+        let predicates = script(r#"fn translate(input) { [input, "", ["a", "b"], true] }"#)
+            .run("ab", &engine, &mut scope, "hi")
+            .unwrap();
+        assert_eq!(predicates[0].texts, vec!["a".to_owned(), "b".to_owned()]);
+        assert_eq!(predicates[0].kind, MatchKind::Script("ab".to_owned()));
+        assert!(predicates[0].can_commit);
+        assert_eq!(
+            script("fn translate(input) {}").run("none", &engine, &mut scope, "hi"),
+            Ok(vec![])
+        );
+        assert_eq!(
+            script("fn translate(input) { [] }").run("none", &engine, &mut scope, "hi"),
+            Ok(vec![])
+        );
+
+        // Object maps.
+        let predicates = script(
+            r#"
+            fn translate(input) {
+                [
+                    #{ texts: "a", score: 50 },
+                    #{ code: "hey", remaining_code: "y", texts: ["b"], can_commit: true },
+                ]
+            }
+        "#,
+        )
+        .run("maps", &engine, &mut scope, "hi")
+        .unwrap();
+        assert_eq!(
+            predicates,
+            vec![
+                Predicate {
+                    code: "hi".to_owned(),
+                    remaining_code: "".to_owned(),
+                    texts: vec!["a".to_owned()],
+                    can_commit: false,
+                    confidence: 0.5,
+                    kind: MatchKind::Script("maps".to_owned()),
+                    source: None,
+                },
+                Predicate {
+                    code: "hey".to_owned(),
+                    remaining_code: "y".to_owned(),
+                    texts: vec!["b".to_owned()],
+                    can_commit: true,
+                    confidence: 1.0,
+                    kind: MatchKind::Script("maps".to_owned()),
+                    source: None,
+                }
+            ]
+        );
+        assert_eq!(
+            script(r#"fn translate(input) { #{ texts: "a" } }"#)
+                .run("map", &engine, &mut scope, "hi")
+                .unwrap()
+                .len(),
+            1
+        );
+        for body in [
+            "#{ code: input }",
+            r#"#{ texts: "a", score: 101 }"#,
+            r#"#{ texts: "a", scores: 1 }"#,
+            r#"[#{ texts: "a" }, [input, "", "a", true]]"#,
+        ] {
+            let script = script(&format!("fn translate(input) {{ {body} }}"));
+            assert!(matches!(
+                script.run("map", &engine, &mut scope, "hi"),
+                Err(ScriptError::InvalidResult { .. })
+            ));
+        }
+
+        // Invalid results.
+        let invalid = script(r#"fn translate(input) { [input, "", 1, true] }"#);
+        assert!(matches!(
+            invalid.run("invalid", &engine, &mut scope, "hi"),
+            Err(ScriptError::InvalidResult { translator, .. }) if translator == "invalid"
+        ));
+        assert!(script(r#"fn translate(input) { [input, ""] }"#)
+            .run("invalid", &engine, &mut scope, "hi")
+            .is_err());
+        assert!(script(r#"fn translate(input) { input }"#)
+            .run("invalid", &engine, &mut scope, "hi")
+            .is_err());
+
+        // Failures.
+        let failing = script(r#"fn translate(input) { throw "oops" }"#);
+        let error = failing
+            .run("failing", &engine, &mut scope, "hi")
+            .unwrap_err();
+        assert_eq!(error.translator(), "failing");
+        assert!(error.to_string().contains("oops"));
+
+        // The failures in a row.
+        assert!(!failing.is_disabled(Some(2)));
+        assert!(failing.run("failing", &engine, &mut scope, "hi").is_err());
+        assert!(failing.is_disabled(Some(2)));
+        assert!(!failing.is_disabled(None));
+        assert!(!invalid.is_disabled(Some(2)));
+    }
+
+    #[test]
+    fn test_limits() {
+        use super::{engine, Script, ScriptError, ScriptLimits};
+        use rhai::Scope;
+        use std::{rc::Rc, time::Duration};
+
+        let limits = ScriptLimits {
+            max_operations: 10_000,
+            max_duration: Duration::from_millis(10),
+            max_string_size: 16,
+            max_array_size: 16,
+        };
+        let engine = engine(&limits, None, &Rc::default());
+        let run = |body: &str| {
+            Script::new(engine.compile(body).unwrap()).run(
+                "limited",
+                &engine,
+                &mut Scope::new(),
+                "hi",
+            )
+        };
+        let is_aborted = |result| matches!(result, Err(ScriptError::Aborted { .. }));
+
+        let fill = |size: usize, data: &str| {
+            format!("fn translate(input) {{ let data = {data}; for i in 0..{size} {{ data += \"a\"; }} }}")
+        };
+        assert!(run(&fill(16, "[]")).is_ok());
+        assert!(run(&fill(16, "\"\"")).is_ok());
+        assert!(is_aborted(run(&fill(17, "[]"))));
+        assert!(is_aborted(run(&fill(17, "\"\""))));
+        assert!(is_aborted(run("fn translate(input) { loop {} }")));
+
+        // The duration.
+        let engine = super::engine(
+            &ScriptLimits {
+                max_operations: 0,
+                ..limits
+            },
+            None,
+            &Rc::default(),
+        );
+        let script = Script::new(engine.compile("fn translate(input) { loop {} }").unwrap());
+        let error = script
+            .run("endless", &engine, &mut Scope::new(), "hi")
+            .unwrap_err();
+        assert!(error.to_string().contains("Time limit"));
+    }
+
+    #[test]
+    fn test_sandbox() {
+        use super::{engine, Script, ScriptLimits};
+        use rhai::Scope;
+        use std::{env, fs, rc::Rc};
+
+        let root = env::temp_dir().join("afrim_translator_sandbox");
+        fs::create_dir_all(root.join("inside")).unwrap();
+        fs::write(
+            root.join("inside/core.rhai"),
+            r#"fn translate(input) { [input, "", "inside", true] }"#,
+        )
+        .unwrap();
+        fs::write(root.join("outside.rhai"), "").unwrap();
+
+        let limits = ScriptLimits::default();
+        let run = |engine: &rhai::Engine, path: &str| {
+            let script = format!(
+                "import {path:?} as core; fn translate(input) {{ core::translate(input) }}"
+            );
+            Script::new(engine.compile(script).unwrap()).run(
+                "sandbox",
+                engine,
+                &mut Scope::new(),
+                "hi",
+            )
+        };
+
+        // Inside the module root.
+        let sandbox = engine(&limits, Some(&root.join("inside")), &Rc::default());
+        let core = root.join("inside/core");
+        let predicates = run(&sandbox, core.to_str().unwrap()).unwrap();
+        assert_eq!(predicates[0].texts, vec!["inside".to_owned()]);
+
+        // Outside of the module root.
+        let outside = root.join("inside/../outside");
+        assert!(run(&sandbox, outside.to_str().unwrap()).is_err());
+
+        // Without module root.
+        assert!(run(
+            &engine(&limits, None, &Rc::default()),
+            core.to_str().unwrap()
+        )
+        .is_err());
+    }
+}
@@ -205,6 +205,28 @@ impl Node {
         self.children.borrow().get(&character).map(Rc::clone)
     }
 
+    /// Returns the children of the node, sorted by key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::Node;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['u', 'u'], "ʉ".to_owned());
+    /// text_buffer.insert(vec!['u', '5'], "û".to_owned());
+    ///
+    /// let node = text_buffer.goto('u').unwrap();
+    /// let keys: Vec<char> = node.children().iter().map(|node| node.key).collect();
+    /// assert_eq!(keys, vec!['5', 'u']);
+    /// ```
+    pub fn children(&self) -> Vec<Rc<Self>> {
+        let mut children: Vec<_> = self.children.borrow().values().map(Rc::clone).collect();
+        children.sort_by_key(|node| node.key);
+
+        children
+    }
+
     /// Extracts the value of the node.
     ///
     /// A node in the text buffer don't always holds a value.
@@ -296,8 +318,119 @@ impl Node {
 /// backspace operations in an input method engine.
 #[derive(Clone)]
 pub struct Cursor {
-    buffer: VecDeque<Rc<Node>>,
+    buffer: VecDeque<Step>,
     root: Rc<Node>,
+    case_rules: Option<Rc<CaseRules>>,
+}
+
+// A position of the cursor in the text buffer.
+#[derive(Clone)]
+struct Step {
+    node: Rc<Node>,
+    // Position in the lowercase sequence, when the case rules apply.
+    folded: Option<Rc<Node>>,
+    // Value of the lowercase sequence, converted to uppercase.
+    value: Option<String>,
+    // Whether the character has been entered as a standalone sequence.
+    literal: bool,
+}
+
+impl Step {
+    fn new(node: Rc<Node>) -> Self {
+        Self {
+            node,
+            folded: None,
+            value: None,
+            literal: false,
+        }
+    }
+
+    // The value of the node always takes precedence.
+    fn value(&self) -> Option<String> {
+        self.node.take().or_else(|| self.value.clone())
+    }
+}
+
+/// Rules to handle the case of the sequences at runtime.
+///
+/// When a sequence starts with an uppercase character, the [`Cursor`] looks for the lowercase
+/// sequence in the text buffer and converts his value to uppercase. It permits to handle the
+/// first-letter capitalization and the caps lock without having to store uppercase sequences in
+/// the text buffer.
+///
+/// **Note**: The uppercase sequences present in the text buffer always take precedence.
+///
+/// # Example
+///
+/// ```
+/// use afrim_memory::{CaseRules, Cursor, Node};
+/// use std::rc::Rc;
+///
+/// let text_buffer = Node::default();
+/// text_buffer.insert(vec!['a', 'f'], "ɑ".to_owned());
+/// text_buffer.insert(vec!['i', '-'], "ɨ".to_owned());
+/// text_buffer.insert(vec!['A', 'f'], "α".to_owned());
+/// let memory = Rc::new(text_buffer);
+///
+/// // A language-specific case mapping.
+/// let mut case_rules = CaseRules::new();
+/// case_rules.insert('ɨ', "Ɨ".to_owned());
+///
+/// let mut cursor = Cursor::new(memory, 16);
+/// cursor.set_case_rules(Some(case_rules));
+///
+/// // First-letter capitalization.
+/// assert_eq!(cursor.hit('I'), None);
+/// assert_eq!(cursor.hit('-'), Some("Ɨ".to_owned()));
+/// // Caps lock.
+/// assert_eq!(cursor.hit('I'), None);
+/// assert_eq!(cursor.hit('-'), Some("Ɨ".to_owned()));
+/// // Uppercase sequence present in the text buffer.
+/// assert_eq!(cursor.hit('A'), None);
+/// assert_eq!(cursor.hit('f'), Some("α".to_owned()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CaseRules {
+    mappings: HashMap<char, String>,
+}
+
+impl CaseRules {
+    /// Initializes the case rules.
+    ///
+    /// By default, the unicode case mapping is used.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the uppercase of a character.
+    ///
+    /// Useful when dealing with language-specific case mappings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::CaseRules;
+    ///
+    /// let mut case_rules = CaseRules::new();
+    /// assert_eq!(case_rules.to_uppercase("ɑi"), "ⱭI".to_owned());
+    ///
+    /// case_rules.insert('ɑ', "Α".to_owned());
+    /// case_rules.insert('i', "İ".to_owned());
+    /// assert_eq!(case_rules.to_uppercase("ɑi"), "Αİ".to_owned());
+    /// ```
+    pub fn insert(&mut self, lowercase: char, uppercase: String) {
+        self.mappings.insert(lowercase, uppercase);
+    }
+
+    /// Converts a text to uppercase.
+    pub fn to_uppercase(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| match self.mappings.get(&c) {
+                Some(uppercase) => uppercase.to_owned(),
+                None => c.to_uppercase().collect(),
+            })
+            .collect()
+    }
 }
 
 impl fmt::Debug for Cursor {
@@ -334,9 +467,64 @@ impl Cursor {
         Self {
             buffer: VecDeque::with_capacity(capacity),
             root,
+            case_rules: None,
         }
     }
 
+    /// Sets the case rules to apply at runtime.
+    ///
+    /// See [`CaseRules`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{CaseRules, Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', 'f'], "ɑ".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 16);
+    /// "Af".chars().for_each(|c| { cursor.hit(c); });
+    /// assert_eq!(cursor.state(), (None, 0, 'f'));
+    ///
+    /// cursor.set_case_rules(Some(CaseRules::new()));
+    /// "Af".chars().for_each(|c| { cursor.hit(c); });
+    /// assert_eq!(cursor.state(), (Some("Ɑ".to_owned()), 2, 'f'));
+    /// ```
+    pub fn set_case_rules(&mut self, case_rules: Option<CaseRules>) {
+        self.case_rules = case_rules.map(Rc::new);
+    }
+
+    /// Replaces the memory of the cursor.
+    ///
+    /// The internal buffer is cleared since it refers to the previous memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', 'f'], "ɑ".to_owned());
+    /// let mut cursor = Cursor::new(Rc::new(text_buffer), 8);
+    /// cursor.hit('a');
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', 'a'], "á".to_owned());
+    /// cursor.set_root(Rc::new(text_buffer));
+    /// assert!(cursor.is_empty());
+    ///
+    /// cursor.hit('a');
+    /// assert_eq!(cursor.hit('a'), Some("á".to_owned()));
+    /// ```
+    pub fn set_root(&mut self, root: Rc<Node>) {
+        self.root = root;
+        self.buffer.clear();
+    }
+
     /// Enters a character in the sequence and returns his corresponding out.
     ///
     /// Permits to simulate the user typing in the input method engine.
@@ -371,30 +559,113 @@ impl Cursor {
     ///   tempory node to represent it in his internal memory. All characters not present in the text
     ///   buffer will be at the same depth that the root node.
     pub fn hit(&mut self, character: char) -> Option<String> {
-        let node = self
-            .buffer
-            .iter()
-            .last()
-            .and_then(|node| node.goto(character))
-            .or_else(|| {
-                // We end the current sequence
-                self.insert(Rc::new(Node::default()));
-                // and start a new one
-                self.root.goto(character)
-            })
-            .unwrap_or_else(|| Rc::new(Node::new(character, 0)));
+        let last = self.buffer.iter().last();
+        let mut node = last.and_then(|step| step.node.goto(character));
+        let mut folded = last
+            .and_then(|step| step.folded.as_ref())
+            .and_then(|folded| self.fold(folded, character));
+
+        if node.is_none() && folded.is_none() {
+            // We end the current sequence
+            self.insert(Step::new(Rc::new(Node::default())));
+            // and start a new one
+            (node, folded) = self.start(character);
+        }
+
+        self.push(character, node, folded)
+    }
+
+    // Returns the positions of a character starting a new sequence.
+    fn start(&self, character: char) -> (Option<Rc<Node>>, Option<Rc<Node>>) {
+        let node = self.root.goto(character);
+        let folded = character
+            .is_uppercase()
+            .then(|| self.fold(&self.root, character))
+            .flatten();
+
+        (node, folded)
+    }
 
-        let out = node.take();
-        self.insert(node);
+    // Moves the cursor to the positions of a character.
+    fn push(
+        &mut self,
+        character: char,
+        node: Option<Rc<Node>>,
+        folded: Option<Rc<Node>>,
+    ) -> Option<String> {
+        let value = folded
+            .as_ref()
+            .and_then(|folded| folded.take())
+            .zip(self.case_rules.as_ref())
+            .map(|(value, case_rules)| case_rules.to_uppercase(&value));
+        let node = node.unwrap_or_else(|| {
+            let depth = folded.as_ref().map(|folded| folded.depth).unwrap_or(0);
+
+            Rc::new(Node::new(character, depth))
+        });
+        let step = Step {
+            node,
+            folded,
+            value,
+            literal: false,
+        };
+
+        let out = step.value();
+        self.insert(step);
 
         out
     }
 
-    fn insert(&mut self, node: Rc<Node>) {
+    // Moves through the lowercase sequence, when the case rules apply.
+    fn fold(&self, node: &Node, character: char) -> Option<Rc<Node>> {
+        self.case_rules.as_ref()?;
+
+        let mut lowercase = character.to_lowercase();
+        let character = lowercase.next().filter(|_| lowercase.next().is_none())?;
+
+        node.goto(character)
+    }
+
+    /// Enters a character as a standalone sequence, without moving through the text buffer.
+    ///
+    /// Useful when a character should be kept as it is, even if it starts or continues a
+    /// sequence in the text buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', 'f'], "ɑ".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 16);
+    /// cursor.hit_literal('a');
+    /// assert_eq!(cursor.hit('f'), None);
+    ///
+    /// assert_eq!(cursor.to_sequence(), vec!['\0', 'a', '\0', 'f']);
+    /// ```
+    pub fn hit_literal(&mut self, character: char) {
+        // We end the current sequence
+        self.insert(Step::new(Rc::new(Node::default())));
+        // and add the character as a temporary node
+        self.insert_literal(character);
+    }
+
+    fn insert_literal(&mut self, character: char) {
+        self.insert(Step {
+            literal: true,
+            ..Step::new(Rc::new(Node::new(character, 0)))
+        });
+    }
+
+    fn insert(&mut self, step: Step) {
         if self.buffer.len() == self.buffer.capacity() {
             self.buffer.pop_front();
         }
-        self.buffer.push_back(node);
+        self.buffer.push_back(step);
     }
 
     /// Removes the last node and returns his corresponding out.
@@ -435,13 +706,13 @@ impl Cursor {
     /// before each new sequence. You can considere it as a delimiter between two sequences. But if
     /// you want clear or verify if the buffer is empty, you can use [Cursor::clear](crate::Cursor::clear) or [Cursor::is_empty](crate::Cursor::is_empty).
     pub fn undo(&mut self) -> Option<String> {
-        let node = self.buffer.pop_back();
+        let step = self.buffer.pop_back();
 
-        node.and_then(|node| {
-            if node.key == '\0' {
+        step.and_then(|step| {
+            if step.node.key == '\0' {
                 self.undo()
             } else {
-                node.take()
+                step.value()
             }
         })
     }
@@ -473,11 +744,51 @@ impl Cursor {
     /// assert_eq!(cursor.hit('_'), Some("ç".to_owned()).to_owned());
     /// ```
     pub fn resume(&mut self) {
-        if self.buffer.iter().last().is_some_and(|node| node.is_root()) {
+        if self
+            .buffer
+            .iter()
+            .last()
+            .is_some_and(|step| step.node.is_root())
+        {
             self.buffer.pop_back();
         }
     }
 
+    /// Ends the current sequence.
+    ///
+    /// By adding an end marker to the current sequence, this method ensures that the next hit
+    /// starts a new sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['c', '_'], "ç".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 8);
+    /// cursor.hit('c');
+    /// assert_eq!(cursor.to_sequence(), vec!['\0', 'c']);
+    ///
+    /// // We don't want the next hit to continue this sequence.
+    /// cursor.end();
+    /// assert_eq!(cursor.to_sequence(), vec!['\0', 'c', '\0']);
+    /// assert_eq!(cursor.hit('_'), None);
+    /// ```
+    pub fn end(&mut self) {
+        if self
+            .buffer
+            .iter()
+            .last()
+            .is_some_and(|step| !step.node.is_root())
+        {
+            self.insert(Step::new(Rc::new(Node::default())));
+        }
+    }
+
     /// Returns the current state of the cursor.
     ///
     /// Permits to know the current position in the memory and also the last hit.
@@ -502,10 +813,123 @@ impl Cursor {
         self.buffer
             .iter()
             .last()
-            .map(|n| (n.take(), n.depth, n.key))
+            .map(|step| (step.value(), step.node.depth, step.node.key))
             .unwrap_or_default()
     }
 
+    /// Returns the characters that can continue the current sequence, along with their
+    /// corresponding out.
+    ///
+    /// The hints are sorted by character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['u', 'u'], "ʉ".to_owned());
+    /// text_buffer.insert(vec!['u', '5'], "û".to_owned());
+    /// text_buffer.insert(vec!['u', 'u', 'a', 'f'], "ʉɑ".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 8);
+    /// cursor.hit('u');
+    /// assert_eq!(
+    ///     cursor.hints(),
+    ///     vec![('5', Some("û".to_owned())), ('u', Some("ʉ".to_owned()))]
+    /// );
+    ///
+    /// cursor.hit('u');
+    /// assert_eq!(cursor.hints(), vec![('a', None)]);
+    /// ```
+    pub fn hints(&self) -> Vec<(char, Option<String>)> {
+        let Some(step) = self.buffer.back() else {
+            return vec![];
+        };
+        let mut hints: Vec<_> = step
+            .node
+            .children()
+            .into_iter()
+            .map(|node| (node.key, node.take()))
+            .collect();
+
+        // The continuations of the lowercase sequence.
+        if let Some((folded, case_rules)) = step.folded.as_ref().zip(self.case_rules.as_ref()) {
+            folded.children().into_iter().for_each(|node| {
+                if hints.iter().all(|(key, _)| *key != node.key) {
+                    let value = node.take().map(|value| case_rules.to_uppercase(&value));
+                    hints.push((node.key, value));
+                }
+            });
+            hints.sort_by_key(|(key, _)| *key);
+        }
+
+        hints
+    }
+
+    /// Returns the values reachable from the current sequence, along with the characters
+    /// remaining to reach them.
+    ///
+    /// The shortest sequences come first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', '2'], "á".to_owned());
+    /// text_buffer.insert(vec!['a', '1'], "à".to_owned());
+    /// text_buffer.insert(vec!['a', 'f', '1'], "ɑ̀".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 8);
+    /// cursor.hit('a');
+    /// assert_eq!(
+    ///     cursor.alternates(),
+    ///     vec![
+    ///         ("1".to_owned(), "à".to_owned()),
+    ///         ("2".to_owned(), "á".to_owned()),
+    ///         ("f1".to_owned(), "ɑ̀".to_owned())
+    ///     ]
+    /// );
+    /// ```
+    pub fn alternates(&self) -> Vec<(String, String)> {
+        let Some(step) = self.buffer.back() else {
+            return vec![];
+        };
+        let mut alternates = vec![];
+        let mut visit = |node: &Node, to_value: &dyn Fn(String) -> String| {
+            let mut queue = VecDeque::from([(String::new(), node.children())]);
+
+            while let Some((sequence, children)) = queue.pop_front() {
+                children.into_iter().for_each(|node| {
+                    let sequence = format!("{sequence}{}", node.key);
+
+                    if let Some(value) = node.take() {
+                        if alternates.iter().all(|(other, _)| *other != sequence) {
+                            alternates.push((sequence.clone(), to_value(value)));
+                        }
+                    }
+                    queue.push_back((sequence, node.children()));
+                });
+            }
+        };
+
+        visit(&step.node, &|value| value);
+
+        // The continuations of the lowercase sequence.
+        if let Some((folded, case_rules)) = step.folded.as_ref().zip(self.case_rules.as_ref()) {
+            visit(folded, &|value| case_rules.to_uppercase(&value));
+            alternates.sort_by_key(|(sequence, _)| sequence.chars().count());
+        }
+
+        alternates
+    }
+
     /// Returns the current sequence in the cursor.
     ///
     /// It's always useful to know what is inside the memory of the cursor for debugging / logging.
@@ -527,7 +951,87 @@ impl Cursor {
     /// assert_eq!(cursor.to_sequence(), vec!['\0', 'z', '\0', '.', '.', 'z']);
     /// ```
     pub fn to_sequence(&self) -> Vec<char> {
-        self.buffer.iter().map(|node| node.key).collect()
+        self.buffer.iter().map(|step| step.node.key).collect()
+    }
+
+    /// Returns the positions in the current sequence of the characters entered with
+    /// [`Cursor::hit_literal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['a', 'f'], "ɑ".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory, 8);
+    /// cursor.hit('a');
+    /// cursor.hit_literal('a');
+    ///
+    /// assert_eq!(cursor.to_sequence(), vec!['\0', 'a', '\0', 'a']);
+    /// assert_eq!(cursor.literals(), vec![3]);
+    /// ```
+    pub fn literals(&self) -> Vec<usize> {
+        self.buffer
+            .iter()
+            .enumerate()
+            .filter_map(|(position, step)| step.literal.then_some(position))
+            .collect()
+    }
+
+    /// Restores the cursor from a sequence, as returned by [`Cursor::to_sequence`].
+    ///
+    /// The characters at the positions of `literals`, as returned by [`Cursor::literals`], are
+    /// restored as if they were entered with [`Cursor::hit_literal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use afrim_memory::{Cursor, Node};
+    /// use std::rc::Rc;
+    ///
+    /// let text_buffer = Node::default();
+    /// text_buffer.insert(vec!['c', '_'], "ç".to_owned());
+    /// let memory = Rc::new(text_buffer);
+    ///
+    /// let mut cursor = Cursor::new(memory.clone(), 8);
+    /// cursor.hit_literal('c');
+    /// "cc_".chars().for_each(|c| { cursor.hit(c); });
+    /// cursor.end();
+    ///
+    /// let mut restored = Cursor::new(memory, 8);
+    /// restored.restore(&cursor.to_sequence(), &cursor.literals());
+    /// assert_eq!(
+    ///     restored.to_sequence(),
+    ///     vec!['\0', 'c', '\0', 'c', '\0', 'c', '_', '\0']
+    /// );
+    /// assert_eq!(restored.literals(), vec![1]);
+    /// assert_eq!(restored.undo(), Some("ç".to_owned()));
+    /// assert_eq!(restored.state(), (None, 1, 'c'));
+    /// ```
+    pub fn restore(&mut self, sequence: &[char], literals: &[usize]) {
+        self.clear();
+
+        sequence
+            .iter()
+            .enumerate()
+            .for_each(|(position, &character)| {
+                let new_sequence = self.buffer.back().is_none_or(|step| step.node.key == '\0');
+
+                if character == '\0' {
+                    self.insert(Step::new(Rc::new(Node::default())));
+                } else if literals.contains(&position) {
+                    self.insert_literal(character);
+                } else if new_sequence {
+                    let (node, folded) = self.start(character);
+                    self.push(character, node, folded);
+                } else {
+                    self.hit(character);
+                }
+            });
     }
 
     /// Clear the memory of the cursor.
@@ -572,7 +1076,11 @@ impl Cursor {
     /// assert!(!cursor.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.buffer.iter().filter(|c| c.key != '\0').count() == 0
+        self.buffer
+            .iter()
+            .filter(|step| step.node.key != '\0')
+            .count()
+            == 0
     }
 }
 
@@ -683,4 +1191,48 @@ mod tests {
         cursor.clear();
         assert_eq!(cursor.to_sequence(), vec![]);
     }
+
+    #[test]
+    fn test_case_rules() {
+        use crate::{utils, CaseRules, Cursor};
+        use std::rc::Rc;
+
+        let data = "af ɑ\naf1 ɑ\u{300}\nAF Α";
+        let root = utils::build_map(utils::load_data(data));
+
+        let mut cursor = Cursor::new(Rc::new(root), 16);
+        cursor.set_case_rules(Some(CaseRules::new()));
+
+        "Af".chars().for_each(|c| {
+            cursor.hit(c);
+        });
+        assert_eq!(cursor.state(), (Some("Ɑ".to_owned()), 2, 'f'));
+        assert_eq!(cursor.hints(), vec![('1', Some("Ɑ\u{300}".to_owned()))]);
+        assert_eq!(cursor.hit('1'), Some("Ɑ\u{300}".to_owned()));
+        assert_eq!(cursor.undo(), Some("Ɑ\u{300}".to_owned()));
+        assert_eq!(cursor.undo(), Some("Ɑ".to_owned()));
+
+        // The uppercase sequence takes precedence.
+        cursor.hit('F');
+        assert_eq!(cursor.state(), (Some("Α".to_owned()), 2, 'F'));
+        assert_eq!(cursor.hints(), vec![('1', Some("Ɑ\u{300}".to_owned()))]);
+        assert_eq!(
+            cursor.alternates(),
+            vec![("1".to_owned(), "Ɑ\u{300}".to_owned())]
+        );
+        assert_eq!(cursor.hit('1'), Some("Ɑ\u{300}".to_owned()));
+        assert_eq!(cursor.to_sequence(), vec!['\0', 'A', 'F', '1']);
+
+        // Only the sequences starting with an uppercase character are concerned.
+        "aF".chars().for_each(|c| {
+            cursor.hit(c);
+        });
+        assert_eq!(cursor.state(), (None, 0, 'F'));
+
+        cursor.set_case_rules(None);
+        "Af".chars().for_each(|c| {
+            cursor.hit(c);
+        });
+        assert_eq!(cursor.state(), (None, 0, 'f'));
+    }
 }
@@ -1,7 +1,8 @@
 #![deny(missing_docs)]
-//! Set of tools to facilitate the loading of data.
+//! Set of tools to facilitate the loading and the usage of data.
 
-use crate::Node;
+use crate::{Cursor, Node};
+use std::rc::Rc;
 
 /// Load the sequential codes from a plain text and returns it.
 ///
@@ -71,6 +72,145 @@ pub fn build_map(data: Vec<Vec<&str>>) -> Node {
     root
 }
 
+/// Build a map from a list of sequential codes, where the modifier keys can be typed before or
+/// after the rest of the sequence.
+///
+/// Each sequence is also reachable with its modifier keys placed anywhere, the modifier keys
+/// and the other keys keeping their respective order. By example, `2a3` is also reachable with
+/// `23a` and `a23`.
+///
+/// **Note**: The sequences explicitly listed always take precedence.
+///
+/// # Example
+///
+/// ```
+/// use afrim_memory::{Cursor, Node, utils};
+/// use std::rc::Rc;
+///
+/// let data = utils::load_data(r#"
+/// a2      á
+/// 2uaf    úɑ́
+/// "#);
+/// let text_buffer = utils::build_map_with_modifiers(data, &['2']);
+/// let memory = Rc::new(text_buffer);
+///
+/// let mut cursor = Cursor::new(memory, 16);
+/// "2a".chars().for_each(|c| { cursor.hit(c); });
+/// assert_eq!(cursor.state(), (Some("á".to_owned()), 2, 'a'));
+///
+/// "uaf2".chars().for_each(|c| { cursor.hit(c); });
+/// assert_eq!(cursor.state(), (Some("úɑ́".to_owned()), 4, '2'));
+///```
+pub fn build_map_with_modifiers(data: Vec<Vec<&str>>, modifiers: &[char]) -> Node {
+    let root = build_map(data.clone());
+
+    data.iter().filter(|e| e.len() == 2).for_each(|e| {
+        reorder_modifiers(e[0], modifiers)
+            .into_iter()
+            .for_each(|sequence| {
+                if find(&root, &sequence).is_none() {
+                    root.insert(sequence, e[1].to_owned());
+                }
+            });
+    });
+
+    root
+}
+
+/// Converts a text as if it was typed in the input method.
+///
+/// # Example
+///
+/// ```
+/// use afrim_memory::utils;
+/// use std::rc::Rc;
+///
+/// let data = utils::load_data("af ɑ\naff ɑɑ\nc_ ç");
+/// let memory = Rc::new(utils::build_map(data));
+///
+/// assert_eq!(utils::transliterate(Rc::clone(&memory), "c_af aff"), "çɑ ɑɑ");
+/// assert_eq!(utils::transliterate(memory, "hello"), "hello");
+/// ```
+pub fn transliterate(memory: Rc<Node>, text: &str) -> String {
+    // Each character adds at most a step and the start of a sequence.
+    let mut cursor = Cursor::new(memory, 2 * text.chars().count() + 1);
+    let mut out = Vec::new();
+
+    text.chars().for_each(|character| {
+        let Some(value) = cursor.hit(character) else {
+            out.push(character);
+            return;
+        };
+
+        // The code of the sequence and its previous value are replaced.
+        let mut previous = cursor.clone();
+        previous.undo();
+        while let (None, 1.., ..) = previous.state() {
+            previous.undo();
+            out.pop();
+        }
+        if let (Some(value), ..) = previous.state() {
+            out.truncate(out.len() - value.chars().count());
+        }
+        out.extend(value.chars());
+    });
+
+    out.into_iter().collect()
+}
+
+// Returns the value of a sequence in the map.
+fn find(root: &Node, sequence: &[char]) -> Option<String> {
+    let (first, rest) = sequence.split_first()?;
+    let node = root.goto(*first)?;
+
+    rest.iter()
+        .try_fold(node, |node, character| node.goto(*character))?
+        .take()
+}
+
+// Returns the other placements of the modifier keys of a sequence.
+//
+// The modifier keys and the other keys keep their respective order.
+fn reorder_modifiers(sequence: &str, modifiers: &[char]) -> Vec<Vec<char>> {
+    let sequence: Vec<char> = sequence.chars().collect();
+    let (keys, base): (Vec<char>, Vec<char>) = sequence
+        .iter()
+        .copied()
+        .partition(|character| modifiers.contains(character));
+
+    if keys.is_empty() || base.is_empty() {
+        return vec![];
+    }
+
+    let mut placements = vec![];
+    interleave(&keys, &base, &mut vec![], &mut placements);
+    placements.retain(|placement| *placement != sequence);
+
+    placements
+}
+
+// Collects the interleavings of two sequences.
+fn interleave(
+    left: &[char],
+    right: &[char],
+    prefix: &mut Vec<char>,
+    interleavings: &mut Vec<Vec<char>>,
+) {
+    match (left.split_first(), right.split_first()) {
+        (None, _) => interleavings.push([prefix.as_slice(), right].concat()),
+        (_, None) => interleavings.push([prefix.as_slice(), left].concat()),
+        (Some((first, rest)), Some((other, others))) => {
+            prefix.push(*first);
+            interleave(rest, right, prefix, interleavings);
+            prefix.pop();
+
+            prefix.push(*other);
+            interleave(left, others, prefix, interleavings);
+            prefix.pop();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -96,4 +236,62 @@ mod tests {
 
         utils::build_map(data);
     }
+
+    #[test]
+    fn test_build_map_with_modifiers() {
+        use crate::utils;
+
+        let data = utils::load_data("a2 á\n2a ā\n2ua úá\n22 ˝\nb b");
+        let root = utils::build_map_with_modifiers(data, &['1', '2']);
+        let value = |sequence: &str| utils::find(&root, &sequence.chars().collect::<Vec<_>>());
+
+        // The sequences explicitly listed take precedence.
+        assert_eq!(value("2a"), Some("ā".to_owned()));
+        assert_eq!(value("a2"), Some("á".to_owned()));
+        assert_eq!(value("ua2"), Some("úá".to_owned()));
+        assert_eq!(value("u2a"), Some("úá".to_owned()));
+        // The sequences without base or modifier are kept as they are.
+        assert_eq!(value("22"), Some("˝".to_owned()));
+        assert_eq!(value("b"), Some("b".to_owned()));
+    }
+
+    #[test]
+    fn test_reorder_modifiers() {
+        use crate::utils;
+
+        let placements = |sequence: &str| {
+            utils::reorder_modifiers(sequence, &['2', '3'])
+                .into_iter()
+                .map(String::from_iter)
+                .collect::<Vec<_>>()
+        };
+
+        // The modifier keys are placed anywhere, in their order.
+        assert_eq!(placements("2a3"), vec!["23a", "a23"]);
+        assert_eq!(placements("a2b"), vec!["2ab", "ab2"]);
+        assert_eq!(
+            placements("2ab3"),
+            vec!["23ab", "2a3b", "a23b", "a2b3", "ab23"]
+        );
+        // Nothing to reorder.
+        assert!(placements("ab").is_empty());
+        assert!(placements("23").is_empty());
+    }
+
+    #[test]
+    fn test_transliterate() {
+        use crate::utils;
+        use std::rc::Rc;
+
+        let data = include_str!("../data/sample.txt");
+        let memory = Rc::new(utils::build_map(utils::load_data(data)));
+
+        assert_eq!(utils::transliterate(Rc::clone(&memory), ""), "");
+        assert_eq!(utils::transliterate(Rc::clone(&memory), "2uaf"), "úɑ́");
+        assert_eq!(
+            utils::transliterate(Rc::clone(&memory), "x 2a 2a_"),
+            "y 2a á̠"
+        );
+        assert_eq!(utils::transliterate(memory, "2aax2ii"), "ááyíí");
+    }
 }
@@ -3,7 +3,7 @@
 //! generic keyboard events and vice versa.
 //!
 
-use afrim_preprocessor::{Key, KeyState, KeyboardEvent, NamedKey::*};
+use afrim_preprocessor::{Code, Key, KeyState, KeyboardEvent, Location, NamedKey::*};
 use rdev::{self};
 
 /// Converts an rdev::Event into a KeyboardEvent.
@@ -11,36 +11,334 @@ pub fn from_event(event: rdev::Event) -> KeyboardEvent {
     let key_char = event
         .name
         .and_then(|c| c.chars().next())
-        .filter(|c| c.is_alphanumeric() || c.is_ascii_punctuation())
+        .filter(|c| !c.is_control())
         .map(|c| Key::Character(c.to_string()));
     let (state, key) = match event.event_type {
-        rdev::EventType::KeyPress(key) => (KeyState::Down, from_key(key)),
-        rdev::EventType::KeyRelease(key) => (KeyState::Up, from_key(key)),
-        _ => Default::default(),
+        rdev::EventType::KeyPress(key) => (KeyState::Down, key),
+        rdev::EventType::KeyRelease(key) => (KeyState::Up, key),
+        _ => return Default::default(),
     };
 
     KeyboardEvent {
-        key: key_char.unwrap_or(key),
+        key: key_char.unwrap_or(from_key(key)),
+        code: from_code(key),
+        location: from_location(key),
         state,
         ..Default::default()
     }
 }
 
 /// Converts an rdev::Key into a Key.
+///
+/// The keys which generate a character are converted based on the US layout.
 pub fn from_key(key: rdev::Key) -> Key {
+    let named_key = match key {
+        rdev::Key::Alt => Alt,
+        rdev::Key::AltGr => AltGraph,
+        rdev::Key::Backspace => Backspace,
+        rdev::Key::CapsLock => CapsLock,
+        rdev::Key::ControlLeft | rdev::Key::ControlRight => Control,
+        rdev::Key::Delete | rdev::Key::KpDelete => Delete,
+        rdev::Key::DownArrow => ArrowDown,
+        rdev::Key::End => End,
+        rdev::Key::Escape => Escape,
+        rdev::Key::F1 => F1,
+        rdev::Key::F2 => F2,
+        rdev::Key::F3 => F3,
+        rdev::Key::F4 => F4,
+        rdev::Key::F5 => F5,
+        rdev::Key::F6 => F6,
+        rdev::Key::F7 => F7,
+        rdev::Key::F8 => F8,
+        rdev::Key::F9 => F9,
+        rdev::Key::F10 => F10,
+        rdev::Key::F11 => F11,
+        rdev::Key::F12 => F12,
+        rdev::Key::Home => Home,
+        rdev::Key::LeftArrow => ArrowLeft,
+        rdev::Key::MetaLeft | rdev::Key::MetaRight => Meta,
+        rdev::Key::PageDown => PageDown,
+        rdev::Key::PageUp => PageUp,
+        rdev::Key::Return | rdev::Key::KpReturn => Enter,
+        rdev::Key::RightArrow => ArrowRight,
+        rdev::Key::ShiftLeft | rdev::Key::ShiftRight => Shift,
+        rdev::Key::Tab => Tab,
+        rdev::Key::UpArrow => ArrowUp,
+        rdev::Key::PrintScreen => PrintScreen,
+        rdev::Key::ScrollLock => ScrollLock,
+        rdev::Key::Pause => Pause,
+        rdev::Key::NumLock => NumLock,
+        rdev::Key::Insert => Insert,
+        rdev::Key::Function => Fn,
+        rdev::Key::Unknown(_) => Unidentified,
+        key => {
+            return from_character_key(key)
+                .map(|c| Key::Character(c.to_string()))
+                .unwrap_or_default()
+        }
+    };
+
+    Key::Named(named_key)
+}
+
+// Returns the character generated by an rdev::Key on the US layout.
+fn from_character_key(key: rdev::Key) -> Option<char> {
+    let character = match key {
+        rdev::Key::Space => ' ',
+        rdev::Key::BackQuote => '`',
+        rdev::Key::Num1 | rdev::Key::Kp1 => '1',
+        rdev::Key::Num2 | rdev::Key::Kp2 => '2',
+        rdev::Key::Num3 | rdev::Key::Kp3 => '3',
+        rdev::Key::Num4 | rdev::Key::Kp4 => '4',
+        rdev::Key::Num5 | rdev::Key::Kp5 => '5',
+        rdev::Key::Num6 | rdev::Key::Kp6 => '6',
+        rdev::Key::Num7 | rdev::Key::Kp7 => '7',
+        rdev::Key::Num8 | rdev::Key::Kp8 => '8',
+        rdev::Key::Num9 | rdev::Key::Kp9 => '9',
+        rdev::Key::Num0 | rdev::Key::Kp0 => '0',
+        rdev::Key::Minus | rdev::Key::KpMinus => '-',
+        rdev::Key::Equal => '=',
+        rdev::Key::KpPlus => '+',
+        rdev::Key::KpMultiply => '*',
+        rdev::Key::KpDivide => '/',
+        rdev::Key::KeyQ => 'q',
+        rdev::Key::KeyW => 'w',
+        rdev::Key::KeyE => 'e',
+        rdev::Key::KeyR => 'r',
+        rdev::Key::KeyT => 't',
+        rdev::Key::KeyY => 'y',
+        rdev::Key::KeyU => 'u',
+        rdev::Key::KeyI => 'i',
+        rdev::Key::KeyO => 'o',
+        rdev::Key::KeyP => 'p',
+        rdev::Key::LeftBracket => '[',
+        rdev::Key::RightBracket => ']',
+        rdev::Key::KeyA => 'a',
+        rdev::Key::KeyS => 's',
+        rdev::Key::KeyD => 'd',
+        rdev::Key::KeyF => 'f',
+        rdev::Key::KeyG => 'g',
+        rdev::Key::KeyH => 'h',
+        rdev::Key::KeyJ => 'j',
+        rdev::Key::KeyK => 'k',
+        rdev::Key::KeyL => 'l',
+        rdev::Key::SemiColon => ';',
+        rdev::Key::Quote => '\'',
+        rdev::Key::BackSlash | rdev::Key::IntlBackslash => '\\',
+        rdev::Key::KeyZ => 'z',
+        rdev::Key::KeyX => 'x',
+        rdev::Key::KeyC => 'c',
+        rdev::Key::KeyV => 'v',
+        rdev::Key::KeyB => 'b',
+        rdev::Key::KeyN => 'n',
+        rdev::Key::KeyM => 'm',
+        rdev::Key::Comma => ',',
+        rdev::Key::Dot => '.',
+        rdev::Key::Slash => '/',
+        _ => return None,
+    };
+
+    Some(character)
+}
+
+/// Converts an rdev::Key into a Code.
+pub fn from_code(key: rdev::Key) -> Code {
+    match key {
+        rdev::Key::Alt => Code::AltLeft,
+        rdev::Key::AltGr => Code::AltRight,
+        rdev::Key::Backspace => Code::Backspace,
+        rdev::Key::CapsLock => Code::CapsLock,
+        rdev::Key::ControlLeft => Code::ControlLeft,
+        rdev::Key::ControlRight => Code::ControlRight,
+        rdev::Key::Delete => Code::Delete,
+        rdev::Key::DownArrow => Code::ArrowDown,
+        rdev::Key::End => Code::End,
+        rdev::Key::Escape => Code::Escape,
+        rdev::Key::F1 => Code::F1,
+        rdev::Key::F2 => Code::F2,
+        rdev::Key::F3 => Code::F3,
+        rdev::Key::F4 => Code::F4,
+        rdev::Key::F5 => Code::F5,
+        rdev::Key::F6 => Code::F6,
+        rdev::Key::F7 => Code::F7,
+        rdev::Key::F8 => Code::F8,
+        rdev::Key::F9 => Code::F9,
+        rdev::Key::F10 => Code::F10,
+        rdev::Key::F11 => Code::F11,
+        rdev::Key::F12 => Code::F12,
+        rdev::Key::Home => Code::Home,
+        rdev::Key::LeftArrow => Code::ArrowLeft,
+        rdev::Key::MetaLeft => Code::MetaLeft,
+        rdev::Key::MetaRight => Code::MetaRight,
+        rdev::Key::PageDown => Code::PageDown,
+        rdev::Key::PageUp => Code::PageUp,
+        rdev::Key::Return => Code::Enter,
+        rdev::Key::RightArrow => Code::ArrowRight,
+        rdev::Key::ShiftLeft => Code::ShiftLeft,
+        rdev::Key::ShiftRight => Code::ShiftRight,
+        rdev::Key::Space => Code::Space,
+        rdev::Key::Tab => Code::Tab,
+        rdev::Key::UpArrow => Code::ArrowUp,
+        rdev::Key::PrintScreen => Code::PrintScreen,
+        rdev::Key::ScrollLock => Code::ScrollLock,
+        rdev::Key::Pause => Code::Pause,
+        rdev::Key::NumLock => Code::NumLock,
+        rdev::Key::BackQuote => Code::Backquote,
+        rdev::Key::Num1 => Code::Digit1,
+        rdev::Key::Num2 => Code::Digit2,
+        rdev::Key::Num3 => Code::Digit3,
+        rdev::Key::Num4 => Code::Digit4,
+        rdev::Key::Num5 => Code::Digit5,
+        rdev::Key::Num6 => Code::Digit6,
+        rdev::Key::Num7 => Code::Digit7,
+        rdev::Key::Num8 => Code::Digit8,
+        rdev::Key::Num9 => Code::Digit9,
+        rdev::Key::Num0 => Code::Digit0,
+        rdev::Key::Minus => Code::Minus,
+        rdev::Key::Equal => Code::Equal,
+        rdev::Key::KeyQ => Code::KeyQ,
+        rdev::Key::KeyW => Code::KeyW,
+        rdev::Key::KeyE => Code::KeyE,
+        rdev::Key::KeyR => Code::KeyR,
+        rdev::Key::KeyT => Code::KeyT,
+        rdev::Key::KeyY => Code::KeyY,
+        rdev::Key::KeyU => Code::KeyU,
+        rdev::Key::KeyI => Code::KeyI,
+        rdev::Key::KeyO => Code::KeyO,
+        rdev::Key::KeyP => Code::KeyP,
+        rdev::Key::LeftBracket => Code::BracketLeft,
+        rdev::Key::RightBracket => Code::BracketRight,
+        rdev::Key::KeyA => Code::KeyA,
+        rdev::Key::KeyS => Code::KeyS,
+        rdev::Key::KeyD => Code::KeyD,
+        rdev::Key::KeyF => Code::KeyF,
+        rdev::Key::KeyG => Code::KeyG,
+        rdev::Key::KeyH => Code::KeyH,
+        rdev::Key::KeyJ => Code::KeyJ,
+        rdev::Key::KeyK => Code::KeyK,
+        rdev::Key::KeyL => Code::KeyL,
+        rdev::Key::SemiColon => Code::Semicolon,
+        rdev::Key::Quote => Code::Quote,
+        rdev::Key::BackSlash => Code::Backslash,
+        rdev::Key::IntlBackslash => Code::IntlBackslash,
+        rdev::Key::KeyZ => Code::KeyZ,
+        rdev::Key::KeyX => Code::KeyX,
+        rdev::Key::KeyC => Code::KeyC,
+        rdev::Key::KeyV => Code::KeyV,
+        rdev::Key::KeyB => Code::KeyB,
+        rdev::Key::KeyN => Code::KeyN,
+        rdev::Key::KeyM => Code::KeyM,
+        rdev::Key::Comma => Code::Comma,
+        rdev::Key::Dot => Code::Period,
+        rdev::Key::Slash => Code::Slash,
+        rdev::Key::Insert => Code::Insert,
+        rdev::Key::KpReturn => Code::NumpadEnter,
+        rdev::Key::KpMinus => Code::NumpadSubtract,
+        rdev::Key::KpPlus => Code::NumpadAdd,
+        rdev::Key::KpMultiply => Code::NumpadMultiply,
+        rdev::Key::KpDivide => Code::NumpadDivide,
+        rdev::Key::Kp0 => Code::Numpad0,
+        rdev::Key::Kp1 => Code::Numpad1,
+        rdev::Key::Kp2 => Code::Numpad2,
+        rdev::Key::Kp3 => Code::Numpad3,
+        rdev::Key::Kp4 => Code::Numpad4,
+        rdev::Key::Kp5 => Code::Numpad5,
+        rdev::Key::Kp6 => Code::Numpad6,
+        rdev::Key::Kp7 => Code::Numpad7,
+        rdev::Key::Kp8 => Code::Numpad8,
+        rdev::Key::Kp9 => Code::Numpad9,
+        rdev::Key::KpDelete => Code::NumpadDecimal,
+        rdev::Key::Function => Code::Fn,
+        rdev::Key::Unknown(_) => Code::Unidentified,
+    }
+}
+
+// Returns the location of an rdev::Key.
+fn from_location(key: rdev::Key) -> Location {
     match key {
-        rdev::Key::Alt => Key::Named(Alt),
-        rdev::Key::AltGr => Key::Named(AltGraph),
-        rdev::Key::Backspace => Key::Named(Backspace),
-        rdev::Key::CapsLock => Key::Named(CapsLock),
-        rdev::Key::ControlLeft => Key::Named(Control),
-        rdev::Key::ControlRight => Key::Named(Control),
-        rdev::Key::ShiftLeft => Key::Named(Shift),
-        rdev::Key::ShiftRight => Key::Named(Shift),
-        rdev::Key::ScrollLock => Key::Named(ScrollLock),
-        rdev::Key::Pause => Key::Named(Pause),
-        rdev::Key::NumLock => Key::Named(NumLock),
-        rdev::Key::Insert => Key::Named(Insert),
-        _ => Default::default(),
+        rdev::Key::Alt | rdev::Key::ControlLeft | rdev::Key::MetaLeft | rdev::Key::ShiftLeft => {
+            Location::Left
+        }
+        rdev::Key::AltGr
+        | rdev::Key::ControlRight
+        | rdev::Key::MetaRight
+        | rdev::Key::ShiftRight => Location::Right,
+        rdev::Key::KpReturn
+        | rdev::Key::KpMinus
+        | rdev::Key::KpPlus
+        | rdev::Key::KpMultiply
+        | rdev::Key::KpDivide
+        | rdev::Key::Kp0
+        | rdev::Key::Kp1
+        | rdev::Key::Kp2
+        | rdev::Key::Kp3
+        | rdev::Key::Kp4
+        | rdev::Key::Kp5
+        | rdev::Key::Kp6
+        | rdev::Key::Kp7
+        | rdev::Key::Kp8
+        | rdev::Key::Kp9
+        | rdev::Key::KpDelete => Location::Numpad,
+        _ => Location::Standard,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_from_event() {
+        use super::*;
+        use std::time::SystemTime;
+
+        let event = |event_type, name: Option<&str>| rdev::Event {
+            time: SystemTime::now(),
+            name: name.map(str::to_owned),
+            event_type,
+        };
+
+        // The name of the key takes precedence.
+        let keyboard_event =
+            from_event(event(rdev::EventType::KeyPress(rdev::Key::KeyQ), Some("a")));
+        assert_eq!(keyboard_event.key, Key::Character("a".to_owned()));
+        assert_eq!(keyboard_event.code, Code::KeyQ);
+        assert_eq!(keyboard_event.state, KeyState::Down);
+
+        // Named keys.
+        [
+            (rdev::Key::Escape, Some("\u{1b}"), Key::Named(Escape)),
+            (rdev::Key::Return, Some("\r"), Key::Named(Enter)),
+            (rdev::Key::Tab, Some("\t"), Key::Named(Tab)),
+            (rdev::Key::UpArrow, None, Key::Named(ArrowUp)),
+            (rdev::Key::LeftArrow, None, Key::Named(ArrowLeft)),
+            (rdev::Key::F1, None, Key::Named(F1)),
+            (rdev::Key::F12, None, Key::Named(F12)),
+            (rdev::Key::Space, Some(" "), Key::Character(" ".to_owned())),
+            (rdev::Key::Kp5, None, Key::Character("5".to_owned())),
+            (rdev::Key::Unknown(0), None, Key::Named(Unidentified)),
+        ]
+        .into_iter()
+        .for_each(|(key, name, expected)| {
+            let keyboard_event = from_event(event(rdev::EventType::KeyRelease(key), name));
+            assert_eq!(keyboard_event.key, expected);
+            assert_eq!(keyboard_event.state, KeyState::Up);
+        });
+
+        // Locations.
+        let keyboard_event = from_event(event(
+            rdev::EventType::KeyPress(rdev::Key::ShiftRight),
+            None,
+        ));
+        assert_eq!(keyboard_event.key, Key::Named(Shift));
+        assert_eq!(keyboard_event.location, Location::Right);
+        assert_eq!(
+            from_event(event(rdev::EventType::KeyPress(rdev::Key::Kp0), None)).location,
+            Location::Numpad
+        );
+
+        // Not a keyboard event.
+        assert_eq!(
+            from_event(event(rdev::EventType::MouseMove { x: 0.0, y: 0.0 }, None)),
+            KeyboardEvent::default()
+        );
     }
 }
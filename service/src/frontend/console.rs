@@ -2,7 +2,7 @@
 //! Console frontend interface for the Afrim.
 //!
 
-use super::{message::Command, Frontend, Predicate};
+use super::{message::Command, Frontend, MatchKind, Predicate};
 use anyhow::{anyhow, Result};
 use std::sync::mpsc::{Receiver, Sender};
 
@@ -13,6 +13,7 @@ pub struct Console {
     predicates: Vec<Predicate>,
     current_predicate_id: usize,
     input: String,
+    hints: Vec<(char, Option<String>)>,
     idle_state: bool,
     tx: Option<Sender<Command>>,
     rx: Option<Receiver<Command>>,
@@ -37,8 +38,10 @@ impl Frontend for Console {
             let command = self.rx.as_ref().unwrap().recv()?;
             match command {
                 Command::InputText(input) => self.set_input_text(input.to_owned()),
+                Command::Hints(hints) => self.set_hints(hints),
                 Command::PageSize(size) => self.set_max_predicates(size),
                 Command::State(state) => self.set_state(state),
+                Command::Profile(profile) => self.set_profile(&profile),
                 Command::Predicate(predicate) => self.add_predicate(predicate.to_owned()),
                 Command::Update => self.display(),
                 Command::Clear => self.clear(),
@@ -88,6 +91,21 @@ impl Console {
         // Input
         println!("input: {}", self.input);
 
+        // Hints
+        if !self.hints.is_empty() {
+            println!(
+                "Hints: {}",
+                self.hints
+                    .iter()
+                    .map(|(key, value)| match value {
+                        Some(value) => format!("{key} → {value}"),
+                        None => key.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            );
+        }
+
         // Predicates
         let page_size = std::cmp::min(self.page_size, self.predicates.len());
         println!(
@@ -100,13 +118,19 @@ impl Console {
                 .take(page_size)
                 .map(|(id, predicate)| {
                     format!(
-                        "{}{}. {} ~{}\t ",
+                        "{}{}. {}{} ~{}\t ",
                         if id == self.current_predicate_id {
                             "*"
                         } else {
                             ""
                         },
                         id + 1,
+                        // The corrections are marked.
+                        if predicate.kind == MatchKind::Fuzzy {
+                            "≈"
+                        } else {
+                            ""
+                        },
                         predicate.texts[0],
                         predicate.remaining_code
                     )
@@ -120,6 +144,7 @@ impl Console {
         self.predicates.clear();
         self.current_predicate_id = 0;
         self.input = String::default();
+        self.hints.clear();
     }
 
     fn set_max_predicates(&mut self, size: usize) {
@@ -131,6 +156,10 @@ impl Console {
         self.input = text;
     }
 
+    fn set_hints(&mut self, hints: Vec<(char, Option<String>)>) {
+        self.hints = hints;
+    }
+
     fn add_predicate(&mut self, predicate: Predicate) {
         predicate
             .texts
@@ -173,6 +202,10 @@ impl Console {
 
         println!("state: {state}");
     }
+
+    fn set_profile(&self, profile: &str) {
+        println!("profile: {profile}");
+    }
 }
 
 #[cfg(test)]
@@ -197,12 +230,15 @@ mod tests {
         assert_eq!(rx2.recv().unwrap(), Command::NOP);
 
         tx1.send(Command::PageSize(10)).unwrap();
+        tx1.send(Command::Profile("default".to_owned())).unwrap();
         tx1.send(Command::InputText("he".to_owned())).unwrap();
+        tx1.send(Command::Hints(vec![('l', None)])).unwrap();
         tx1.send(Command::Predicate(Predicate {
             code: "hell".to_owned(),
             remaining_code: "llo".to_owned(),
             texts: vec!["hello".to_owned()],
             can_commit: false,
+            ..Default::default()
         }))
         .unwrap();
         tx1.send(Command::Predicate(Predicate {
@@ -210,6 +246,7 @@ mod tests {
             remaining_code: "lip".to_owned(),
             texts: vec![],
             can_commit: false,
+            ..Default::default()
         }))
         .unwrap();
         tx1.send(Command::Predicate(Predicate {
@@ -217,6 +254,7 @@ mod tests {
             remaining_code: "s".to_owned(),
             texts: vec!["".to_owned()],
             can_commit: false,
+            ..Default::default()
         }))
         .unwrap();
         tx1.send(Command::Predicate(Predicate {
@@ -224,6 +262,7 @@ mod tests {
             remaining_code: "al".to_owned(),
             texts: vec!["health".to_owned()],
             can_commit: false,
+            ..Default::default()
         }))
         .unwrap();
 
@@ -236,7 +275,8 @@ mod tests {
                 code: "heal".to_owned(),
                 remaining_code: "al".to_owned(),
                 texts: vec!["health".to_owned()],
-                can_commit: false
+                can_commit: false,
+                ..Default::default()
             })
         );
 
@@ -248,7 +288,8 @@ mod tests {
                 code: "hell".to_owned(),
                 remaining_code: "llo".to_owned(),
                 texts: vec!["hello".to_owned()],
-                can_commit: false
+                can_commit: false,
+                ..Default::default()
             })
         );
 
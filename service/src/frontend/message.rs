@@ -11,10 +11,15 @@ pub enum Command {
     Position((f64, f64)),
     /// Informs about the current input text.
     InputText(String),
+    /// Informs about the next characters that can continue the current input,
+    /// along with the text that each of them would generate.
+    Hints(Vec<(char, Option<String>)>),
     /// Informs about the max numbers of predicate by page.
     PageSize(usize),
     /// Whether the backend is in IDLE.
     State(bool),
+    /// Informs about the active profile.
+    Profile(String),
     /// Information about a predicate.
     ///
     /// Use cases:
@@ -33,6 +38,12 @@ pub enum Command {
     SelectNextPredicate,
     /// Request to get the selected predicate..
     SelectedPredicate,
+    /// Requests to enable or disable a dictionary of the active profile.
+    Dictionary((String, bool)),
+    /// Requests to add a text for a code in the user dictionary.
+    AddWord((String, String)),
+    /// Requests to remove the texts added by the user for a code.
+    RemoveWord(String),
     /// Informs about no operation available.
     NOP,
     /// Requests to end the communication.
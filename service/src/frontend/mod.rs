@@ -5,7 +5,7 @@
 mod console;
 mod message;
 
-pub use afrim_translator::Predicate;
+pub use afrim_translator::{MatchKind, Predicate};
 use anyhow::Result;
 pub use console::Console;
 pub use message::Command;
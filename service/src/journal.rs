@@ -0,0 +1,171 @@
+#![deny(missing_docs)]
+//! Set of tools to record and replay the activity of the afrim.
+//!
+//! A journal is saved as JSON lines, one entry per line.
+//!
+//! Besides the activity of the preprocessor, the journal records the actions of the user (e.g.
+//! the switches of profile, the words added or the predicates selected) and the files saved by
+//! the user when the recording started.
+
+use crate::{apply, build, process, Config};
+pub use afrim_preprocessor::{Divergence, Entry, Journal};
+use anyhow::{Context, Result};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+/// Appends the entries of the journal to a file.
+///
+/// The entries saved are removed from the journal.
+pub fn save(journal: &mut Journal, file: &mut impl Write) -> Result<()> {
+    journal.drain().try_for_each(|entry| -> Result<()> {
+        serde_json::to_writer(&mut *file, &entry)?;
+        writeln!(file)?;
+
+        Ok(())
+    })?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Loads a journal from a file.
+pub fn load(path: &Path) -> Result<Journal> {
+    let file =
+        File::open(path).with_context(|| format!("Couldn't open the journal file {path:?}."))?;
+    let mut journal = Journal::new();
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid entry at line {} of {path:?}.", number + 1))?;
+        journal.push(entry);
+    }
+
+    Ok(journal)
+}
+
+/// Replays a journal through a fresh preprocessor and translators built from the configuration.
+///
+/// The files saved by the user are neither read nor written, the journal provides their content.
+/// Returns the first point where the activity differs from the journal, if any.
+pub fn replay(config: &Config, journal: &Journal) -> Result<Option<Divergence>> {
+    let (mut preprocessor, mut profiles, settings) = build(config)?;
+    let mut active_profile = 0;
+    let mut failure = None;
+
+    let result = journal.replay_with(&mut preprocessor, |preprocessor, entry| match entry {
+        Entry::Event { event, timestamp } => {
+            process(
+                preprocessor,
+                &mut profiles[active_profile].translator,
+                event.clone(),
+                *timestamp,
+                settings,
+            );
+        }
+        Entry::Action(action) if failure.is_none() => {
+            failure = serde_json::from_str(action)
+                .context("Invalid action.")
+                .and_then(|action| {
+                    apply(
+                        action,
+                        preprocessor,
+                        &mut profiles,
+                        &mut active_profile,
+                        config.core.as_ref(),
+                    )
+                })
+                .err();
+        }
+        _ => (),
+    });
+
+    match failure {
+        Some(error) => Err(error),
+        None => Ok(result.err()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_save_and_load() {
+        use crate::journal::{self, Entry, Journal};
+        use afrim_preprocessor::{Command, Key, KeyboardEvent};
+        use std::{env, fs::File, time::Duration};
+
+        let mut journal = Journal::new();
+        journal.push(Entry::Event {
+            event: KeyboardEvent {
+                key: Key::Character("a".to_owned()),
+                ..Default::default()
+            },
+            timestamp: Some(Duration::from_millis(10)),
+        });
+        journal.push(Entry::Commit("ɑ".to_owned()));
+        journal.push(Entry::Command(Command::Pause));
+        let expected = journal.clone();
+
+        let path = env::temp_dir().join("afrim_journal.jsonl");
+        let mut file = File::create(&path).unwrap();
+        journal::save(&mut journal, &mut file).unwrap();
+        assert!(journal.entries().is_empty());
+
+        assert_eq!(journal::load(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_replay_actions() {
+        use crate::{apply, build, journal, process, Action, Config};
+        use afrim_preprocessor::{Entry, Key, KeyboardEvent};
+        use std::path::Path;
+
+        let config = Config::from_file(Path::new("./data/test.toml")).unwrap();
+        let (mut preprocessor, mut profiles, settings) = build(&config).unwrap();
+        let mut active_profile = 0;
+
+        // We record a session using a word of the user.
+        preprocessor.start_recording();
+        let action = Action::AddWord(("xy".to_owned(), "xylophone".to_owned()));
+        apply(
+            action,
+            &mut preprocessor,
+            &mut profiles,
+            &mut active_profile,
+            config.core.as_ref(),
+        )
+        .unwrap();
+        for key in ["x", "y"] {
+            let event = KeyboardEvent {
+                key: Key::Character(key.to_owned()),
+                ..Default::default()
+            };
+            process(
+                &mut preprocessor,
+                &mut profiles[active_profile].translator,
+                event,
+                None,
+                settings,
+            );
+            while preprocessor.pop_queue().is_some() {}
+        }
+        let mut journal = preprocessor.stop_recording().unwrap();
+        assert!(matches!(&journal.entries()[0], Entry::Action(_)));
+        assert!(journal
+            .entries()
+            .contains(&Entry::Commit("xylophone".to_owned())));
+
+        assert!(journal::replay(&config, &journal).unwrap().is_none());
+
+        // An unknown action can't be replayed.
+        journal.push(Entry::Action("\"Unknown\"".to_owned()));
+        assert!(journal::replay(&config, &journal).is_err());
+    }
+}
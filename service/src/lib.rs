@@ -1,61 +1,592 @@
 mod convert;
 pub mod frontend;
+#[cfg(feature = "serde")]
+pub mod journal;
 
 pub use afrim_config::Config;
-use afrim_preprocessor::{utils, Command as EventCmd, Preprocessor};
-use afrim_translator::Translator;
-use anyhow::{Context, Result};
+use afrim_config::CoreConfig;
+#[cfg(feature = "serde")]
+use afrim_preprocessor::Entry;
+use afrim_preprocessor::{
+    utils, CaseRules, Code, Command as EventCmd, KeyboardEvent, Node, Preprocessor,
+};
+#[cfg(feature = "serde")]
+use afrim_translator::Ranking;
+#[cfg(feature = "rhai")]
+use afrim_translator::ScriptLimits;
+use afrim_translator::{MatchKind, Predicate, Translator};
+use anyhow::{anyhow, Context, Result};
 use enigo::{Direction, Enigo, Key, Keyboard};
 use frontend::{Command as GUICmd, Frontend};
+use indexmap::IndexMap;
 use rdev::{self, EventType, Key as E_Key};
-use std::{rc::Rc, sync::mpsc, thread};
+#[cfg(feature = "serde")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use std::{
+    fs::File,
+    rc::Rc,
+    sync::mpsc,
+    thread,
+    time::{Duration, UNIX_EPOCH},
+};
+
+// An input method available in the afrim.
+struct Profile {
+    name: String,
+    memory: Rc<Node>,
+    translator: Translator,
+    core: Option<CoreConfig>,
+    #[cfg(feature = "serde")]
+    ranking_file: Option<PathBuf>,
+    // The number of selections already saved in the ranking file.
+    #[cfg(feature = "serde")]
+    saved_selections: u64,
+    // The words added by the user, layered over the translation table.
+    user_dictionary: IndexMap<String, Vec<String>>,
+    #[cfg(feature = "serde")]
+    user_dictionary_file: Option<PathBuf>,
+}
+
+impl Profile {
+    // Builds the memory and the translator of a configuration.
+    fn new(name: String, config: &Config, auto_commit: bool) -> Result<Self> {
+        let modifier_keys = config
+            .core
+            .as_ref()
+            .and_then(|core| core.modifier_keys.clone())
+            .unwrap_or_default();
+        let memory = Rc::new(utils::build_map_with_modifiers(
+            config
+                .extract_data()
+                .iter()
+                .map(|(key, value)| vec![key.as_str(), value.as_str()])
+                .collect(),
+            &modifier_keys,
+        ));
+        let mut translator = Translator::new(IndexMap::new(), auto_commit);
+        config
+            .extract_dictionaries()
+            .into_iter()
+            .for_each(|(name, dictionary)| {
+                translator.add_dictionary(name, dictionary.translation, dictionary.priority)
+            });
+        #[cfg(feature = "rhai")]
+        config
+            .extract_translators()
+            .context("Failed to load translators.")?
+            .into_iter()
+            .for_each(|(name, ast)| translator.register(name, ast));
+        #[cfg(feature = "rhai")]
+        {
+            let default = ScriptLimits::default();
+            let core = config.core.as_ref();
+
+            translator.set_module_root(Some(config.directory().to_path_buf()));
+            translator.set_memory(Some(Rc::clone(&memory)));
+            translator.set_script_limits(ScriptLimits {
+                max_operations: core
+                    .and_then(|core| core.script_max_operations)
+                    .unwrap_or(default.max_operations),
+                max_duration: core
+                    .and_then(|core| core.script_timeout)
+                    .map_or(default.max_duration, Duration::from_millis),
+                max_string_size: core
+                    .and_then(|core| core.script_max_string_size)
+                    .unwrap_or(default.max_string_size),
+                max_array_size: core
+                    .and_then(|core| core.script_max_array_size)
+                    .unwrap_or(default.max_array_size),
+            });
+        }
+        #[cfg(feature = "fuzzy")]
+        if let Some(core) = config.core.as_ref() {
+            translator.set_max_distance(core.max_distance.unwrap_or(2));
+            translator.set_similarity_threshold(core.similarity_threshold.unwrap_or(0.7));
+        }
+
+        Ok(Self {
+            name,
+            memory,
+            translator,
+            core: config.core.clone(),
+            #[cfg(feature = "serde")]
+            ranking_file: None,
+            #[cfg(feature = "serde")]
+            saved_selections: 0,
+            user_dictionary: IndexMap::new(),
+            #[cfg(feature = "serde")]
+            user_dictionary_file: None,
+        })
+    }
+
+    // Restores the selections and the words saved by the user, and keeps saving them.
+    #[cfg(feature = "serde")]
+    fn open(&mut self) -> Result<()> {
+        let core = self.core.as_ref();
+        let mut ranking = Ranking::new();
+        let mut user_dictionary = IndexMap::new();
+
+        // We restore the selections learned in the previous sessions.
+        self.ranking_file = core
+            .and_then(|core| core.ranking_file.as_ref())
+            .map(PathBuf::from);
+        if let Some(ranking_file) = self.ranking_file.as_ref().filter(|file| file.exists()) {
+            let loaded = fs::read_to_string(ranking_file)
+                .with_context(|| format!("Couldn't open the ranking file {ranking_file:?}."))
+                .and_then(|content| {
+                    serde_json::from_str(&content)
+                        .with_context(|| format!("Invalid ranking file {ranking_file:?}."))
+                });
+
+            // The selections will be learned again, it's not worth to stop the afrim.
+            match loaded {
+                Ok(loaded) => ranking = loaded,
+                Err(error) => eprintln!("{error:#}"),
+            }
+        }
+
+        // We restore the words of the user.
+        self.user_dictionary_file = core
+            .and_then(|core| core.user_dictionary_file.as_ref())
+            .map(PathBuf::from);
+        if let Some(user_dictionary_file) = self
+            .user_dictionary_file
+            .as_ref()
+            .filter(|file| file.exists())
+        {
+            let content = fs::read_to_string(user_dictionary_file).with_context(|| {
+                format!("Couldn't open the user dictionary {user_dictionary_file:?}.")
+            })?;
+            user_dictionary = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid user dictionary {user_dictionary_file:?}."))?;
+        }
+
+        self.restore(ranking, user_dictionary);
+        self.saved_selections = self.translator.ranking().selections();
+
+        Ok(())
+    }
+
+    // Restores the selections and the words of the user, without saving them.
+    #[cfg(feature = "serde")]
+    fn restore(&mut self, ranking: Ranking, user_dictionary: IndexMap<String, Vec<String>>) {
+        self.translator.set_ranking(ranking);
+        user_dictionary.iter().for_each(|(code, texts)| {
+            Self::layer(&mut self.translator, code, texts);
+        });
+        self.user_dictionary = user_dictionary;
+    }
+
+    // Returns a setting of the profile, or of the main configuration if missing.
+    fn setting<T>(
+        &self,
+        main: Option<&CoreConfig>,
+        get: impl Fn(&CoreConfig) -> Option<T>,
+    ) -> Option<T> {
+        self.core
+            .as_ref()
+            .and_then(&get)
+            .or_else(|| main.and_then(get))
+    }
+
+    // Configures the preprocessor for this profile.
+    //
+    // The settings of the afrim itself (see the Settings) are not changed.
+    fn configure(&self, preprocessor: &mut Preprocessor, main: Option<&CoreConfig>) {
+        preprocessor.set_memory(self.memory.clone());
+        preprocessor.set_physical_keys(
+            self.setting(main, |core| core.physical_keys)
+                .unwrap_or(false),
+        );
+        preprocessor.set_escape_key(self.setting(main, |core| core.escape_key));
+        preprocessor.set_timeout(
+            self.setting(main, |core| core.sequence_timeout)
+                .map(Duration::from_millis),
+        );
+        preprocessor.set_separators(
+            self.setting(main, |core| core.separators.clone())
+                .unwrap_or_default(),
+        );
+        preprocessor.set_history_size(self.setting(main, |core| core.history_size).unwrap_or(10));
+        preprocessor.set_alternates(self.setting(main, |core| core.alternates).unwrap_or(false));
+        let case_rules = self
+            .setting(main, |core| core.auto_capitalize)
+            .unwrap_or(true)
+            .then(|| {
+                let mut case_rules = CaseRules::new();
+                self.setting(main, |core| core.case_mapping.clone())
+                    .into_iter()
+                    .flatten()
+                    .for_each(|(lowercase, uppercase)| {
+                        case_rules.insert(lowercase, uppercase);
+                    });
+
+                case_rules
+            });
+        preprocessor.set_case_rules(case_rules);
+    }
+
+    // Puts the texts of the user before the texts of the translation table.
+    fn layer(translator: &mut Translator, code: &str, texts: &[String]) {
+        let mut layered = texts.to_vec();
+        layered.extend(
+            translator
+                .get(code)
+                .into_iter()
+                .flatten()
+                .filter(|text| !texts.contains(text))
+                .cloned(),
+        );
+
+        translator.insert(code.to_owned(), layered);
+    }
+
+    // Adds a word of the user.
+    fn add_word(&mut self, code: String, text: String) -> Result<()> {
+        let texts = self.user_dictionary.entry(code.clone()).or_default();
+        if !texts.contains(&text) {
+            texts.insert(0, text);
+        }
+        Self::layer(&mut self.translator, &code, &self.user_dictionary[&code]);
+
+        self.save_user_dictionary()
+    }
+
+    // Removes the words of the user for a code.
+    //
+    // The texts of the translation table are kept.
+    fn remove_word(&mut self, code: &str) -> Result<()> {
+        let Some(texts) = self.user_dictionary.shift_remove(code) else {
+            return Ok(());
+        };
+        let remaining: Vec<String> = self
+            .translator
+            .get(code)
+            .into_iter()
+            .flatten()
+            .filter(|text| !texts.contains(text))
+            .cloned()
+            .collect();
+
+        if remaining.is_empty() {
+            self.translator.remove(code);
+        } else {
+            self.translator.insert(code.to_owned(), remaining);
+        }
+
+        self.save_user_dictionary()
+    }
+
+    // Saves the words of the user.
+    fn save_user_dictionary(&self) -> Result<()> {
+        #[cfg(feature = "serde")]
+        if let Some(user_dictionary_file) = self.user_dictionary_file.as_ref() {
+            let content = serde_json::to_string_pretty(&self.user_dictionary)?;
+            write(user_dictionary_file, &content).with_context(|| {
+                format!("Couldn't save the user dictionary {user_dictionary_file:?}.")
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // Saves the selections learned since the last save.
+    fn save_ranking(&mut self) -> Result<()> {
+        #[cfg(feature = "serde")]
+        if let Some(ranking_file) = self
+            .ranking_file
+            .as_ref()
+            .filter(|_| self.translator.ranking().selections() != self.saved_selections)
+        {
+            // A failed save is retried at the next selection.
+            self.saved_selections = self.translator.ranking().selections();
+            let content = serde_json::to_string(self.translator.ranking())?;
+            write(ranking_file, &content)
+                .with_context(|| format!("Couldn't save the ranking file {ranking_file:?}."))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Writes a file through a temporary file, so that an interruption never leaves it half-written.
+#[cfg(feature = "serde")]
+fn write(path: &Path, content: &str) -> Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+// An action of the user, other than the keyboard events.
+//
+// The actions are recorded in the journal, to be replayed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Action {
+    // Switches to the next profile.
+    NextProfile,
+    Dictionary((String, bool)),
+    AddWord((String, String)),
+    RemoveWord(String),
+    // Learns the selection of a text for a code.
+    Learn((String, String)),
+    // Restores the selections and the words of the user of a profile.
+    #[cfg(feature = "serde")]
+    Restore {
+        profile: usize,
+        ranking: Ranking,
+        user_dictionary: IndexMap<String, Vec<String>>,
+    },
+}
+
+// Applies an action of the user, and records it in the journal if any.
+fn apply(
+    action: Action,
+    preprocessor: &mut Preprocessor,
+    profiles: &mut [Profile],
+    active_profile: &mut usize,
+    core: Option<&CoreConfig>,
+) -> Result<()> {
+    #[cfg(feature = "serde")]
+    if let Some(journal) = preprocessor.journal_mut() {
+        journal.push(Entry::Action(serde_json::to_string(&action)?));
+    }
+
+    match action {
+        Action::NextProfile => {
+            *active_profile = (*active_profile + 1) % profiles.len();
+            profiles[*active_profile].configure(preprocessor, core);
+        }
+        Action::Dictionary((name, enabled)) => {
+            profiles[*active_profile]
+                .translator
+                .set_dictionary_enabled(&name, enabled);
+        }
+        // The words are kept for the session even if they couldn't be saved.
+        Action::AddWord((code, text)) => {
+            if let Err(error) = profiles[*active_profile].add_word(code, text) {
+                eprintln!("{error:#}");
+            }
+        }
+        Action::RemoveWord(code) => {
+            if let Err(error) = profiles[*active_profile].remove_word(&code) {
+                eprintln!("{error:#}");
+            }
+        }
+        Action::Learn((code, text)) => {
+            profiles[*active_profile].translator.learn(&code, &text);
+        }
+        #[cfg(feature = "serde")]
+        Action::Restore {
+            profile,
+            ranking,
+            user_dictionary,
+        } => {
+            profiles
+                .get_mut(profile)
+                .context("Unknown profile.")?
+                .restore(ranking, user_dictionary);
+        }
+    }
+
+    Ok(())
+}
+
+// Settings of the afrim.
+#[derive(Clone, Copy)]
+struct Settings {
+    auto_commit: bool,
+    page_size: usize,
+    commit_on_separator: bool,
+    // The keys of the special functions, pressed with Ctrl.
+    revert_key: Code,
+    profile_key: Code,
+}
+
+// Returns the key of a special function, named after its position on a US keyboard.
+fn hotkey(name: Option<&str>, default: Code) -> Result<Code> {
+    name.map_or(Ok(default), |name| {
+        name.parse()
+            .map_err(|_| anyhow!("Invalid key {name:?} for a special function."))
+    })
+}
+
+// Builds the preprocessor and the profiles from the configuration.
+//
+// The main profile is the first one. The files saved by the user are not opened.
+fn build(config: &Config) -> Result<(Preprocessor, Vec<Profile>, Settings)> {
+    let core = config.core.as_ref();
+    let settings = Settings {
+        auto_commit: core.and_then(|core| core.auto_commit).unwrap_or(false),
+        page_size: core.and_then(|core| core.page_size).unwrap_or(10),
+        commit_on_separator: core
+            .and_then(|core| core.commit_on_separator)
+            .unwrap_or(false),
+        // Ctrl+Alt is reported for AltGr, which types the diacritics on many layouts.
+        revert_key: hotkey(core.and_then(|core| core.revert_key.as_deref()), Code::F8)?,
+        // Ctrl+Tab switches the tabs of most applications.
+        profile_key: hotkey(core.and_then(|core| core.profile_key.as_deref()), Code::F9)?,
+    };
+    let buffer_size = core.and_then(|core| core.buffer_size).unwrap_or(32);
+    let profile = Profile::new("default".to_owned(), config, settings.auto_commit)?;
+    let mut preprocessor = Preprocessor::new(profile.memory.clone(), buffer_size);
+    profile.configure(&mut preprocessor, core);
+    let mut profiles = vec![profile];
+    for (name, config) in config
+        .extract_profiles()
+        .context("Failed to load profiles.")?
+    {
+        let profile = Profile::new(name.clone(), &config, settings.auto_commit)
+            .with_context(|| format!("Failed to build the profile {name:?}."))?;
+        profiles.push(profile);
+    }
+
+    Ok((preprocessor, profiles, settings))
+}
+
+// Processes a keyboard event.
+//
+// Returns the input and the predicates to display if the input changed.
+fn process(
+    preprocessor: &mut Preprocessor,
+    translator: &mut Translator,
+    event: KeyboardEvent,
+    timestamp: Option<Duration>,
+    settings: Settings,
+) -> Option<(String, Vec<Predicate>)> {
+    let (changed, _committed) = match timestamp {
+        Some(timestamp) => preprocessor.process_with_timestamp(event, timestamp),
+        None => preprocessor.process(event),
+    };
+
+    if !changed {
+        return None;
+    }
+
+    let input = preprocessor.get_input();
+    #[cfg(feature = "rhai")]
+    translator.set_context(preprocessor.get_history());
+    let predicates: Vec<Predicate> = translator
+        .translate_top_k(&input, settings.page_size * 2)
+        .into_iter()
+        .filter(|predicate| {
+            if predicate.texts.is_empty() {
+                false
+            } else if settings.auto_commit && predicate.can_commit && is_implicit(&predicate.kind) {
+                translator.learn(&predicate.code, &predicate.texts[0]);
+                preprocessor.commit(predicate.texts[0].to_owned());
+                false
+            } else {
+                true
+            }
+        })
+        .collect();
+
+    // The failures of the translators are logged, they don't stop the afrim.
+    #[cfg(feature = "rhai")]
+    translator
+        .take_errors()
+        .iter()
+        .for_each(|error| eprintln!("{error}"));
+
+    // The top predicate allowed to be committed implicitly will be at the end of the word.
+    if settings.commit_on_separator {
+        let candidate = predicates
+            .iter()
+            .find(|predicate| is_implicit(&predicate.kind))
+            .map(|predicate| predicate.texts[0].to_owned());
+        preprocessor.set_candidate(candidate);
+    }
+
+    Some((input, predicates))
+}
+
+// Whether a predicate of this kind can be committed without being selected by the user.
+//
+// The corrections and the completions of the input are never committed without the consent of
+// the user.
+fn is_implicit(kind: &MatchKind) -> bool {
+    matches!(kind, MatchKind::Exact | MatchKind::Script(_))
+}
 
 /// Starts the afrim.
-pub fn run(
+pub fn run(config: Config, frontend: impl Frontend + std::marker::Send + 'static) -> Result<()> {
+    start(config, frontend, None)
+}
+
+/// Starts the afrim and records a journal of its activity.
+///
+/// The journal is saved progressively in the given file.
+/// See the [`journal`] module to replay it.
+#[cfg(feature = "serde")]
+pub fn run_recording(
+    config: Config,
+    frontend: impl Frontend + std::marker::Send + 'static,
+    journal_file: &Path,
+) -> Result<()> {
+    let file = File::create(journal_file)
+        .with_context(|| format!("Couldn't create the journal file {journal_file:?}."))?;
+
+    start(config, frontend, Some(file))
+}
+
+fn start(
     config: Config,
     mut frontend: impl Frontend + std::marker::Send + 'static,
+    journal_file: Option<File>,
 ) -> Result<()> {
     // State.
     let mut is_ctrl_released = true;
     let mut idle = false;
+    let mut held_key = None;
+    let mut alternates_displayed = false;
 
     // Configuration of the afrim.
-    let memory = utils::build_map(
-        config
-            .extract_data()
-            .iter()
-            .map(|(key, value)| vec![key.as_str(), value.as_str()])
-            .collect(),
-    );
-    let (buffer_size, auto_commit, page_size) = config
-        .core
-        .as_ref()
-        .map(|core| {
-            (
-                core.buffer_size.unwrap_or(32),
-                core.auto_commit.unwrap_or(false),
-                core.page_size.unwrap_or(10),
-            )
-        })
-        .unwrap_or((32, false, 10));
+    let (mut preprocessor, mut profiles, settings) = build(&config)?;
+    let mut active_profile = 0;
     let mut keyboard = Enigo::new(&Default::default()).unwrap();
-    let mut preprocessor = Preprocessor::new(Rc::new(memory), buffer_size);
-    #[cfg(not(feature = "rhai"))]
-    let translator = Translator::new(config.extract_translation(), auto_commit);
-    #[cfg(feature = "rhai")]
-    let mut translator = Translator::new(config.extract_translation(), auto_commit);
-    #[cfg(feature = "rhai")]
-    config
-        .extract_translators()
-        .context("Failed to load translators.")?
-        .into_iter()
-        .for_each(|(name, ast)| translator.register(name, ast));
+    #[cfg(feature = "serde")]
+    for profile in profiles.iter_mut() {
+        profile.open().with_context(|| {
+            format!(
+                "Failed to open the files of the profile {:?}.",
+                profile.name
+            )
+        })?;
+    }
+    if journal_file.is_some() {
+        preprocessor.start_recording();
+
+        // The replay starts from the files saved by the user.
+        #[cfg(feature = "serde")]
+        for index in 0..profiles.len() {
+            let action = Action::Restore {
+                profile: index,
+                ranking: profiles[index].translator.ranking().clone(),
+                user_dictionary: profiles[index].user_dictionary.clone(),
+            };
+            apply(
+                action,
+                &mut preprocessor,
+                &mut profiles,
+                &mut active_profile,
+                config.core.as_ref(),
+            )?;
+        }
+    }
 
     // Configuration of the frontend.
     let (frontend_tx1, frontend_rx1) = mpsc::channel();
     let (frontend_tx2, frontend_rx2) = mpsc::channel();
 
-    frontend_tx1.send(GUICmd::PageSize(page_size))?;
+    frontend_tx1.send(GUICmd::PageSize(settings.page_size))?;
+    frontend_tx1.send(GUICmd::Profile(profiles[active_profile].name.clone()))?;
     let screen_size = rdev::display_size().unwrap();
     frontend_tx1.send(GUICmd::ScreenSize(screen_size))?;
 
@@ -117,16 +648,48 @@ pub fn run(
 
                 frontend_tx1.send(GUICmd::SelectedPredicate)?;
                 if let GUICmd::Predicate(predicate) = frontend_rx2.recv()? {
-                    preprocessor.commit(
-                        predicate
+                    if alternates_displayed {
+                        preprocessor.select_alternate(&predicate.remaining_code);
+                    } else {
+                        let text = predicate
                             .texts
                             .first()
                             .unwrap_or(&String::default())
-                            .to_owned(),
-                    );
+                            .to_owned();
+
+                        let action = Action::Learn((predicate.code, text.clone()));
+                        apply(
+                            action,
+                            &mut preprocessor,
+                            &mut profiles,
+                            &mut active_profile,
+                            config.core.as_ref(),
+                        )?;
+                        preprocessor.commit(text);
+                    }
+                    frontend_tx1.send(GUICmd::Clear)?;
+                }
+            }
+            EventType::KeyRelease(key)
+                if !is_ctrl_released && convert::from_code(key) == settings.revert_key =>
+            {
+                if preprocessor.revert() {
                     frontend_tx1.send(GUICmd::Clear)?;
                 }
             }
+            EventType::KeyRelease(key)
+                if !is_ctrl_released && convert::from_code(key) == settings.profile_key =>
+            {
+                apply(
+                    Action::NextProfile,
+                    &mut preprocessor,
+                    &mut profiles,
+                    &mut active_profile,
+                    config.core.as_ref(),
+                )?;
+                frontend_tx1.send(GUICmd::Clear)?;
+                frontend_tx1.send(GUICmd::Profile(profiles[active_profile].name.clone()))?;
+            }
             _ if !is_ctrl_released => (),
             // GUI events.
             EventType::MouseMove { x, y } => {
@@ -134,29 +697,35 @@ pub fn run(
             }
             // Process events.
             _ => {
-                let (changed, _committed) = preprocessor.process(convert::from_event(event));
-
-                if changed {
-                    let input = preprocessor.get_input();
+                let timestamp = event.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+                // The repetitions of a held key are not reported as such.
+                let repeat = match event.event_type {
+                    EventType::KeyPress(key) => held_key.replace(key) == Some(key),
+                    EventType::KeyRelease(key) if held_key == Some(key) => {
+                        held_key = None;
+                        false
+                    }
+                    _ => false,
+                };
+                let keyboard_event = KeyboardEvent {
+                    repeat,
+                    ..convert::from_event(event)
+                };
 
+                if let Some((input, predicates)) = process(
+                    &mut preprocessor,
+                    &mut profiles[active_profile].translator,
+                    keyboard_event,
+                    Some(timestamp),
+                    settings,
+                ) {
+                    alternates_displayed = false;
                     frontend_tx1.send(GUICmd::Clear)?;
-
-                    translator
-                        .translate(&input)
-                        .into_iter()
-                        .take(page_size * 2)
-                        .try_for_each(|predicate| -> Result<()> {
-                            if predicate.texts.is_empty() {
-                            } else if auto_commit && predicate.can_commit {
-                                preprocessor.commit(predicate.texts[0].to_owned());
-                            } else {
-                                frontend_tx1.send(GUICmd::Predicate(predicate))?;
-                            }
-
-                            Ok(())
-                        })?;
-
+                    for predicate in predicates {
+                        frontend_tx1.send(GUICmd::Predicate(predicate))?;
+                    }
                     frontend_tx1.send(GUICmd::InputText(input))?;
+                    frontend_tx1.send(GUICmd::Hints(preprocessor.get_hints()))?;
                     frontend_tx1.send(GUICmd::Update)?;
                 }
             }
@@ -180,9 +749,41 @@ pub fn run(
                 EventCmd::Resume => {
                     rdev::simulate(&EventType::KeyRelease(E_Key::Pause)).unwrap();
                 }
+                EventCmd::Alternates(alternates) => {
+                    let input = preprocessor.get_input();
+
+                    alternates_displayed = true;
+                    frontend_tx1.send(GUICmd::Clear)?;
+                    for (remaining_code, text) in alternates {
+                        frontend_tx1.send(GUICmd::Predicate(Predicate {
+                            code: format!("{input}{remaining_code}"),
+                            remaining_code,
+                            texts: vec![text],
+                            can_commit: true,
+                            confidence: 1.0,
+                            kind: MatchKind::Exact,
+                            source: None,
+                        }))?;
+                    }
+                    frontend_tx1.send(GUICmd::InputText(input))?;
+                    frontend_tx1.send(GUICmd::Update)?;
+                }
+                _ => (),
             };
         }
 
+        // Save the activity recorded.
+        #[cfg(feature = "serde")]
+        if let (Some(journal), Some(mut file)) = (preprocessor.journal_mut(), journal_file.as_ref())
+        {
+            journal::save(journal, &mut file).context("Failed to save the journal.")?;
+        }
+
+        // Save the selections learned.
+        if let Err(error) = profiles[active_profile].save_ranking() {
+            eprintln!("{error:#}");
+        }
+
         // Consult the frontend to know if there have some requests.
         frontend_tx1.send(GUICmd::NOP)?;
         match frontend_rx2.recv()? {
@@ -191,6 +792,33 @@ pub fn run(
                 idle = state;
                 frontend_tx1.send(GUICmd::State(idle))?;
             }
+            GUICmd::Dictionary(dictionary) => {
+                apply(
+                    Action::Dictionary(dictionary),
+                    &mut preprocessor,
+                    &mut profiles,
+                    &mut active_profile,
+                    config.core.as_ref(),
+                )?;
+            }
+            GUICmd::AddWord(word) => {
+                apply(
+                    Action::AddWord(word),
+                    &mut preprocessor,
+                    &mut profiles,
+                    &mut active_profile,
+                    config.core.as_ref(),
+                )?;
+            }
+            GUICmd::RemoveWord(code) => {
+                apply(
+                    Action::RemoveWord(code),
+                    &mut preprocessor,
+                    &mut profiles,
+                    &mut active_profile,
+                    config.core.as_ref(),
+                )?;
+            }
             _ => (),
         }
     }
@@ -321,21 +949,29 @@ mod tests {
         output!(textfield, format!("{LIMIT}uuɑ"));
         input!(Escape, typing_speed_ms);
 
+        // We verify that the revert works as expected
+        input!(KeyA KeyF, typing_speed_ms);
+        rdev::simulate(&KeyPress(ControlLeft)).unwrap();
+        input!(F8, typing_speed_ms);
+        rdev::simulate(&KeyRelease(ControlLeft)).unwrap();
+        output!(textfield, format!("{LIMIT}uuɑaf"));
+        input!(Escape, typing_speed_ms);
+
         // We verify the auto capitalization works as expected
         input!(CapsLock KeyA CapsLock KeyF, typing_speed_ms);
         input!(CapsLock KeyA CapsLock KeyF KeyF, typing_speed_ms);
         input!(KeyA KeyF KeyF, typing_speed_ms);
-        output!(textfield, format!("{LIMIT}uuɑαⱭⱭɑɑ"));
+        output!(textfield, format!("{LIMIT}uuɑafαⱭⱭɑɑ"));
         input!(Escape, typing_speed_ms);
 
         // We verify that the translation work as expected
         input!(KeyH KeyE KeyL KeyL KeyO, typing_speed_ms);
-        output!(textfield, format!("{LIMIT}uuɑαⱭⱭɑɑhi"));
+        output!(textfield, format!("{LIMIT}uuɑafαⱭⱭɑɑhi"));
         #[cfg(not(feature = "rhai"))]
         input!(Escape KeyH Escape KeyE KeyL KeyL KeyO, typing_speed_ms);
         #[cfg(feature = "rhai")]
         input!(Escape KeyH KeyI, typing_speed_ms);
-        output!(textfield, format!("{LIMIT}uuɑαⱭⱭɑɑhihello"));
+        output!(textfield, format!("{LIMIT}uuɑafαⱭⱭɑɑhihello"));
         input!(Escape, typing_speed_ms);
 
         // We verify that the predicate selection work as expected
@@ -349,13 +985,13 @@ mod tests {
         rdev::simulate(&KeyPress(ControlLeft)).unwrap();
         input!(Space, typing_speed_ms);
         rdev::simulate(&KeyRelease(ControlLeft)).unwrap();
-        output!(textfield, format!("{LIMIT}uuɑαⱭⱭɑɑhihellohealth"));
+        output!(textfield, format!("{LIMIT}uuɑafαⱭⱭɑɑhihellohealth"));
         input!(Escape, typing_speed_ms);
 
         // We verify that we don't have a conflict
         // between the translator and the processor
         input!(KeyV KeyU KeyU KeyE, typing_speed_ms);
-        output!(textfield, format!("{LIMIT}uuɑαⱭⱭɑɑhihellohealthvʉe"));
+        output!(textfield, format!("{LIMIT}uuɑafαⱭⱭɑɑhihellohealthvʉe"));
 
         // Test the idle state from the frontend.
         input!(Escape Num8 KeyS KeyT KeyQ KeyT KeyE Num8, typing_speed_ms);
@@ -400,4 +1036,88 @@ mod tests {
         // Wait the simulation to end properly.
         simulation_thread.join().unwrap();
     }
+
+    #[test]
+    fn test_hotkey() {
+        use crate::hotkey;
+        use afrim_preprocessor::Code;
+
+        assert_eq!(hotkey(None, Code::F8).unwrap(), Code::F8);
+        assert_eq!(hotkey(Some("KeyR"), Code::F8).unwrap(), Code::KeyR);
+        assert!(hotkey(Some("Ctrl"), Code::F8).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "fuzzy")]
+    fn test_commit_on_separator() {
+        use crate::{process, Settings};
+        use afrim_preprocessor::{utils, Code, Command, Key, KeyboardEvent, Preprocessor};
+        use afrim_translator::Translator;
+        use indexmap::IndexMap;
+        use std::rc::Rc;
+
+        let mut preprocessor = Preprocessor::new(Rc::new(utils::build_map(vec![])), 32);
+        preprocessor.set_separators(vec![' ']);
+        let mut dictionary = IndexMap::new();
+        dictionary.insert("jump".to_owned(), vec!["sauter".to_owned()]);
+        dictionary.insert("jumper".to_owned(), vec!["sauteur".to_owned()]);
+        let mut translator = Translator::new(dictionary, false);
+        let settings = Settings {
+            auto_commit: false,
+            page_size: 10,
+            commit_on_separator: true,
+            revert_key: Code::F8,
+            profile_key: Code::F9,
+        };
+        let mut commits = |word: &str| {
+            word.chars().for_each(|character| {
+                let event = KeyboardEvent {
+                    key: Key::Character(character.to_string()),
+                    ..Default::default()
+                };
+                process(&mut preprocessor, &mut translator, event, None, settings);
+            });
+
+            std::iter::from_fn(|| preprocessor.pop_queue())
+                .filter_map(|command| match command {
+                    Command::CommitText(text) => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Only the exact predicates are committed.
+        assert_eq!(commits("jump "), vec!["sauter ".to_owned()]);
+        assert!(commits("jum ").is_empty());
+        assert!(commits("junp ").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_open() {
+        use crate::{write, Config, Profile};
+        use std::{env, fs, path::Path};
+
+        let ranking_file = env::temp_dir().join("afrim_ranking.json");
+        let mut config = Config::from_file(Path::new("./data/test.toml")).unwrap();
+        config.core.as_mut().unwrap().ranking_file =
+            Some(ranking_file.to_string_lossy().into_owned());
+
+        // A corrupt ranking is replaced by an empty one.
+        fs::write(&ranking_file, "{\"selections\": 3, \"usa").unwrap();
+        let mut profile = Profile::new("test".to_owned(), &config, false).unwrap();
+        profile.open().unwrap();
+        assert!(profile.translator.ranking().is_empty());
+
+        // The ranking is saved entirely.
+        profile.translator.learn("hi", "hello");
+        profile.save_ranking().unwrap();
+        let mut profile = Profile::new("test".to_owned(), &config, false).unwrap();
+        profile.open().unwrap();
+        assert_eq!(profile.translator.ranking().selections(), 1);
+
+        write(&ranking_file, "{}").unwrap();
+        assert_eq!(fs::read_to_string(&ranking_file).unwrap(), "{}");
+        assert!(!ranking_file.with_extension("json.tmp").exists());
+    }
 }
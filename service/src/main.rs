@@ -1,4 +1,6 @@
 use afrim::{frontend, run, Config};
+#[cfg(feature = "serde")]
+use afrim::{journal, run_recording};
 use clap::Parser;
 use std::process;
 
@@ -12,6 +14,16 @@ struct Args {
     /// Only verify if the configuration file is valid.
     #[arg(long, action)]
     check: bool,
+
+    /// Record a journal of the activity in a file.
+    #[cfg(feature = "serde")]
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<std::path::PathBuf>,
+
+    /// Replay a journal and report the first point where the output differs.
+    #[cfg(feature = "serde")]
+    #[arg(long, value_name = "FILE")]
+    replay: Option<std::path::PathBuf>,
 }
 
 fn main() {
@@ -23,10 +35,42 @@ fn main() {
         process::exit(1);
     });
 
-    if !args.check {
-        run(conf, frontend).unwrap_or_else(|err| {
+    if args.check {
+        return;
+    }
+
+    #[cfg(feature = "serde")]
+    if let Some(journal_file) = args.replay {
+        let journal = journal::load(&journal_file).unwrap_or_else(|err| {
+            eprintln!("Problem with journal file: {err:?}");
+            process::exit(1);
+        });
+
+        match journal::replay(&conf, &journal) {
+            Ok(None) => println!("The replay matches the journal."),
+            Ok(Some(divergence)) => {
+                eprintln!("{divergence}");
+                process::exit(2);
+            }
+            Err(err) => {
+                eprintln!("Application error: {err:?}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    #[cfg(feature = "serde")]
+    if let Some(journal_file) = args.record {
+        run_recording(conf, frontend, &journal_file).unwrap_or_else(|err| {
             eprintln!("Application error: {err:?}");
             process::exit(1);
         });
+        return;
     }
+
+    run(conf, frontend).unwrap_or_else(|err| {
+        eprintln!("Application error: {err:?}");
+        process::exit(1);
+    });
 }